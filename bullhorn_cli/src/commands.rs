@@ -0,0 +1,713 @@
+use crate::{platforms::*, post::Post, *};
+use std::path::{Path, PathBuf};
+
+/// Post opened from the command line, and where its state is kept
+struct Source {
+    post: Post,
+    state_path: PathBuf,
+    root: PathBuf,
+}
+
+impl Source {
+    /// Open `file` and fill in front matter from its repository.  `None` if it isn't a file.
+    fn open(file: &Path, settings: &Settings) -> Result<Option<Self>> {
+        if !file.is_file() {
+            warn!("File not found, skipping: {}", file.display());
+            return Ok(None);
+        }
+        let mut post = Post::open(file.to_owned())?;
+        post.apply(settings);
+
+        // Post "original" represented by canonical URL
+        #[cfg(feature = "github_pages")]
+        let root = {
+            let git = github_pages::GithubPages::new(&post, settings.clone())?;
+            git.publish(&mut post)?;
+            git.workdir().map(Path::to_owned)
+        };
+        #[cfg(not(feature = "github_pages"))]
+        let root = None;
+        // State is kept in root of the repository, or next to the post if there isn't one
+        let root = root
+            .or_else(|| post.path.parent().map(Path::to_owned))
+            .filter(|root| !root.as_os_str().is_empty())
+            .unwrap_or_else(|| PathBuf::from("."));
+        let state_path = settings
+            .state
+            .as_ref()
+            .map(PathBuf::from)
+            .unwrap_or_else(|| root.join(state::DEFAULT_PATH));
+        Ok(Some(Self {
+            post,
+            state_path,
+            root,
+        }))
+    }
+}
+
+/// Markdown files in `paths`, walking directories and expanding glob patterns, filtered by
+/// `settings.include`, `settings.exclude`, and `settings.since`
+fn find_posts(paths: &[String], settings: &Settings) -> Result<Vec<PathBuf>> {
+    let include = to_patterns(&settings.include)?;
+    let exclude = to_patterns(&settings.exclude)?;
+    let mut found = vec![];
+    for path in paths {
+        if !path.contains(&['*', '?', '['][..]) {
+            walk(PathBuf::from(path), &mut found)?;
+            continue;
+        }
+        let matches = glob::glob(path).with_context(|| format!("Invalid pattern: {}", path))?;
+        for path in matches {
+            let path = path?;
+            if path.is_dir() || is_markdown(&path) {
+                walk(path, &mut found)?;
+            }
+        }
+    }
+
+    let is_match = |patterns: &[glob::Pattern], path: &Path| {
+        patterns
+            .iter()
+            .any(|pattern| path.ancestors().any(|path| pattern.matches_path(path)))
+    };
+    let mut seen = std::collections::BTreeSet::new();
+    found.retain(|path| {
+        (include.is_empty() || is_match(&include, path))
+            && !is_match(&exclude, path)
+            && seen.insert(path.clone())
+    });
+    match &settings.since {
+        Some(since) => changed_since(found, since, settings),
+        None => Ok(found),
+    }
+}
+
+/// Only the `files` added or modified in their git repository since revision `since`
+#[cfg(feature = "github_pages")]
+fn changed_since(files: Vec<PathBuf>, since: &str, settings: &Settings) -> Result<Vec<PathBuf>> {
+    // Changed files in each repository, keyed by its working directory
+    let mut changes = std::collections::BTreeMap::new();
+    let mut changed = vec![];
+    for file in files {
+        let git = github_pages::GithubPages::open(&file, settings.clone())?;
+        let workdir = match git.workdir() {
+            Some(workdir) => workdir.canonicalize()?,
+            None => continue,
+        };
+        if !changes.contains_key(&workdir) {
+            let paths: std::collections::BTreeSet<PathBuf> = git
+                .changed_since(since)?
+                .iter()
+                .map(|path| workdir.join(path))
+                .collect();
+            changes.insert(workdir.clone(), paths);
+        }
+        let is_changed = file
+            .canonicalize()
+            .map_or(false, |file| changes[&workdir].contains(&file));
+        if is_changed {
+            changed.push(file);
+        } else {
+            debug!("Unchanged since {}, skipping: {}", since, file.display());
+        }
+    }
+    Ok(changed)
+}
+
+#[cfg(not(feature = "github_pages"))]
+fn changed_since(_files: Vec<PathBuf>, _since: &str, _settings: &Settings) -> Result<Vec<PathBuf>> {
+    Err(Error::Unsupported {
+        platform: "this build".to_owned(),
+        operation: "--since without the `github_pages` feature".to_owned(),
+    }
+    .into())
+}
+
+/// Add `path` to `found`, or the markdown files with front matter under it if it's a directory
+fn walk(path: PathBuf, found: &mut Vec<PathBuf>) -> Result<()> {
+    if !path.is_dir() {
+        found.push(path);
+        return Ok(());
+    }
+    let entries = walkdir::WalkDir::new(path).sort_by(|a, b| a.file_name().cmp(b.file_name()));
+    for entry in entries {
+        let entry = entry?;
+        if !entry.file_type().is_file() || !is_markdown(entry.path()) {
+            continue;
+        }
+        match std::fs::read_to_string(entry.path()) {
+            // e.g. README.md
+            Ok(text) if !post::has_front_matter(&text) => {
+                warn!(
+                    "Skipping file without front matter: {}",
+                    entry.path().display()
+                )
+            }
+            // Files that can't be read are reported when they're opened
+            _ => found.push(entry.into_path()),
+        }
+    }
+    Ok(())
+}
+
+fn is_markdown(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|ext| ext.to_str()),
+        Some("md") | Some("markdown")
+    )
+}
+
+/// Patterns match anywhere in a path, e.g. `_drafts/` matches `blog/_drafts/post.md`
+fn to_patterns(globs: &[String]) -> Result<Vec<glob::Pattern>> {
+    globs
+        .iter()
+        .map(|glob| {
+            let pattern = glob.trim_end_matches('/');
+            let pattern = if pattern.starts_with('/') || pattern.starts_with("**") {
+                pattern.to_owned()
+            } else {
+                format!("**/{}", pattern)
+            };
+            glob::Pattern::new(&pattern).with_context(|| format!("Invalid pattern: {}", glob))
+        })
+        .collect()
+}
+
+/// Open `file` and the state it's recorded in.  `None` if it isn't a file.
+fn open_post<'a>(
+    file: &Path,
+    settings: &Settings,
+    ledger: &'a mut state::Ledger,
+) -> Result<Option<(Post, &'a mut state::State)>> {
+    let Source {
+        post,
+        state_path,
+        root,
+    } = match Source::open(file, settings)? {
+        Some(source) => source,
+        None => return Ok(None),
+    };
+    let state = ledger.get_mut(state_path, root)?;
+    Ok(Some((post, state)))
+}
+
+/// Publish `files` to each enabled platform
+pub async fn publish(opts: &Opts, files: &[String]) -> Result<report::Summary> {
+    let platforms = platforms::enabled(opts);
+    let summary = publish_to(&platforms, &opts.settings, files).await?;
+    summary.write(&opts.settings)?;
+    Ok(summary)
+}
+
+async fn publish_to(
+    platforms: &[Box<dyn Platform>],
+    settings: &Settings,
+    files: &[String],
+) -> Result<report::Summary> {
+    let mut summary = report::Summary::default();
+    let mut ledger = state::Ledger::default();
+
+    for file in find_posts(files, settings)? {
+        // A post that can't be opened fails on every platform, but doesn't stop the others
+        let (post, state) = match open_post(&file, settings, &mut ledger) {
+            Ok(Some(opened)) => opened,
+            Ok(None) => continue,
+            Err(err) => {
+                error!("Unable to open post {}: {:#}", file.display(), err);
+                let outcomes = platforms
+                    .iter()
+                    .map(|platform| PublishOutcome::failed(platform.as_ref(), &err))
+                    .collect();
+                summary.add_failed(&file, outcomes);
+                continue;
+            }
+        };
+
+        let records: Vec<_> = platforms
+            .iter()
+            .map(|platform| find_record(state, &post, platform.name()))
+            .collect();
+        let futures = platforms.iter().zip(&records).map(|(platform, record)| {
+            try_publish(platform.as_ref(), &post, settings, record.as_ref())
+        });
+        let outcomes = futures::future::join_all(futures).await;
+        if !settings.dry {
+            state.update(&post, &outcomes);
+            // Saved after each post so articles already published are recorded even if the
+            // run stops early
+            state.save()?;
+            if settings.write_back {
+                if let Err(err) = write_back(&post, &outcomes) {
+                    error!("Unable to write front matter {:?}: {:#}", post.path, err);
+                }
+            }
+        }
+        summary.add(&post, outcomes);
+    }
+    Ok(summary)
+}
+
+/// Where `post` was published on `platform`, from the state or else its front matter
+fn find_record(state: &state::State, post: &Post, platform: &str) -> Option<state::Record> {
+    state.get(post, platform).cloned().or_else(|| {
+        post.front_matter
+            .bullhorn
+            .get(platform)
+            .map(state::Record::from)
+    })
+}
+
+/// Write the canonical URL, date, and where `post` was published into its file.  Other changes
+/// made to the post (e.g. by `--draft`) aren't written, and only the fields written are changed.
+fn write_back(post: &Post, outcomes: &[PublishOutcome]) -> Result<()> {
+    let text = std::fs::read_to_string(&post.path)?;
+    let source = Post::from_string(text.clone())?.front_matter;
+    let mut fields = vec![];
+    if let (None, Some(url)) = (&source.canonical_url, &post.front_matter.canonical_url) {
+        fields.push(("canonical_url", Some(url.as_str().into())));
+    }
+    if let (None, Some(date)) = (&source.date, &post.front_matter.date) {
+        fields.push(("date", Some(date.to_string().into())));
+    }
+    let mut bullhorn = source.bullhorn.clone();
+    for outcome in outcomes {
+        match (&outcome.status, &outcome.remote_id) {
+            (Status::Deleted, _) => {
+                bullhorn.remove(outcome.platform);
+            }
+            (Status::Failed, _) | (_, None) => {}
+            (_, Some(id)) => {
+                let crosspost = post::Crosspost {
+                    id: id.clone(),
+                    url: outcome.remote_url.clone(),
+                };
+                bullhorn.insert(outcome.platform.to_owned(), crosspost);
+            }
+        }
+    }
+    if bullhorn != source.bullhorn {
+        let value = Some(bullhorn)
+            .filter(|bullhorn| !bullhorn.is_empty())
+            .map(serde_yaml::to_value)
+            .transpose()?;
+        fields.push(("bullhorn", value));
+    }
+
+    let new_text = post::set_fields(&text, &fields)?;
+    if new_text != text {
+        std::fs::write(&post.path, new_text)?;
+        info!("Updated front matter: {:?}", post.path);
+    }
+    Ok(())
+}
+
+/// Show whether each of `files` is missing, in sync, or out of date on each enabled platform,
+/// and any articles whose canonical URL doesn't match one of the posts
+pub async fn status(opts: &Opts, files: &[String]) -> Result<()> {
+    let platforms = platforms::enabled(opts);
+    let mut ledger = state::Ledger::default();
+    let mut entries = vec![];
+    let mut canonical_urls = std::collections::BTreeSet::new();
+
+    for file in find_posts(files, &opts.settings)? {
+        let (post, state) = match open_post(&file, &opts.settings, &mut ledger) {
+            Ok(Some(opened)) => opened,
+            Ok(None) => continue,
+            Err(err) => {
+                error!("Unable to open post {}: {:#}", file.display(), err);
+                entries.extend(platforms.iter().map(|platform| report::StatusEntry {
+                    post: Some(file.clone()),
+                    platform: platform.name(),
+                    status: report::SyncStatus::Failed,
+                    remote_id: None,
+                    remote_url: None,
+                    changed: vec![],
+                    error: Some(format!("{:#}", err)),
+                }));
+                continue;
+            }
+        };
+
+        let records: Vec<_> = platforms
+            .iter()
+            .map(|platform| find_record(state, &post, platform.name()))
+            .collect();
+        let futures = platforms
+            .iter()
+            .zip(&records)
+            .map(|(platform, record)| check_status(platform.as_ref(), &post, record.as_ref()));
+        entries.extend(futures::future::join_all(futures).await);
+        if let Some(url) = &post.front_matter.canonical_url {
+            canonical_urls.insert(url.clone());
+        }
+    }
+
+    for platform in &platforms {
+        match platform.list().await {
+            Ok(articles) => {
+                entries.extend(find_orphans(&articles, &canonical_urls).into_iter().map(
+                    |article| report::StatusEntry {
+                        post: None,
+                        platform: platform.name(),
+                        status: report::SyncStatus::Orphaned,
+                        remote_id: Some(article.id.clone()),
+                        remote_url: article.url.clone(),
+                        changed: vec![],
+                        error: None,
+                    },
+                ))
+            }
+            Err(err) => debug!("{}: Unable to find orphans: {:#}", platform.name(), err),
+        }
+    }
+
+    let text = report::render_status(&entries, &opts.settings.report)?;
+    report::write(&opts.settings, &text)
+}
+
+async fn check_status(
+    platform: &dyn Platform,
+    post: &Post,
+    record: Option<&state::Record>,
+) -> report::StatusEntry {
+    use report::SyncStatus;
+
+    let mut entry = report::StatusEntry {
+        post: Some(post.path.clone()),
+        platform: platform.name(),
+        status: SyncStatus::Missing,
+        remote_id: None,
+        remote_url: None,
+        changed: vec![],
+        error: None,
+    };
+    let existing = match record {
+        Some(record) => Ok(Some(record.into())),
+        None => platform.find_existing(post).await,
+    };
+    let existing: RemoteArticle = match existing {
+        Ok(Some(existing)) => existing,
+        Ok(None) => return entry,
+        Err(err) => {
+            entry.status = SyncStatus::Failed;
+            entry.error = Some(format!("{:#}", err));
+            return entry;
+        }
+    };
+    entry.remote_id = Some(existing.id.clone());
+    entry.remote_url = existing.url.clone();
+    entry.status = match platform.get_content(&existing).await {
+        Ok(Some(current)) => {
+            entry.changed = current.changed(&platform.to_content(post));
+            if entry.changed.is_empty() {
+                SyncStatus::InSync
+            } else {
+                SyncStatus::OutOfDate
+            }
+        }
+        // Fall back to whether the post changed since it was last published
+        Ok(None) => match record.and_then(|record| record.hash.as_ref()) {
+            Some(hash) if Some(hash) == post.content_hash().ok().as_ref() => SyncStatus::InSync,
+            Some(_) => SyncStatus::OutOfDate,
+            None => SyncStatus::Published,
+        },
+        Err(err) => {
+            entry.error = Some(format!("{:#}", err));
+            SyncStatus::Failed
+        }
+    };
+    entry
+}
+
+/// Articles with a canonical URL on the same site as `canonical_urls` that isn't one of them
+fn find_orphans<'a>(
+    articles: &'a [ListedArticle],
+    canonical_urls: &std::collections::BTreeSet<String>,
+) -> Vec<&'a ListedArticle> {
+    let sites: std::collections::BTreeSet<&str> =
+        canonical_urls.iter().filter_map(|url| site(url)).collect();
+    articles
+        .iter()
+        .filter(|article| match &article.canonical_url {
+            Some(url) => {
+                !canonical_urls.contains(url) && site(url).map_or(false, |s| sites.contains(s))
+            }
+            None => false,
+        })
+        .collect()
+}
+
+/// Scheme and host of `url`, e.g. `https://user.github.io`
+fn site(url: &str) -> Option<&str> {
+    let start = url.find("://")? + 3;
+    let end = url[start..].find('/').map_or(url.len(), |end| start + end);
+    Some(&url[..end])
+}
+
+/// List the user's articles on `platform`
+pub async fn list(opts: &Opts, platform: &Platforms) -> Result<()> {
+    // All is every enabled platform with credentials, same as when publishing
+    let platforms = match platform {
+        Platforms::All => platforms::enabled(opts),
+        platform => platforms::create(opts, platform).into_iter().collect(),
+    };
+    if platforms.is_empty() {
+        return Err(Error::NotFound {
+            expected: format!("Credentials for {:?}", platform),
+        }
+        .into());
+    }
+    let mut articles = vec![];
+    for platform in &platforms {
+        let listed = platform
+            .list()
+            .await
+            .with_context(|| format!("Unable to list {} articles", platform.name()))?;
+        articles.extend(listed);
+    }
+    let text = report::render_articles(&articles, &opts.settings.report)?;
+    report::write(&opts.settings, &text)
+}
+
+/// Check that each of `files` can be parsed
+pub fn validate(opts: &Opts, files: &[String]) -> Result<()> {
+    let mut invalid = 0;
+    for file in find_posts(files, &opts.settings)? {
+        match Post::open(file.clone()) {
+            Ok(post) => println!("ok: {} ({})", file.display(), post.front_matter.title),
+            Err(err) => {
+                println!("invalid: {}: {:#}", file.display(), err);
+                invalid += 1;
+            }
+        }
+    }
+    if invalid > 0 {
+        return Err(Error::Failed).with_context(|| format!("{} invalid post(s)", invalid));
+    }
+    Ok(())
+}
+
+/// Show the configuration in use, with credentials hidden
+pub fn config(opts: &Opts) -> Result<()> {
+    println!("config: {}", config_path(&opts.settings)?.display());
+    for (key, value) in opts.to_config() {
+        println!("{}: {}", key, value.as_deref().unwrap_or("<not set>"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Git repository in a new temporary directory
+    #[cfg(feature = "github_pages")]
+    fn repo(name: &str) -> Result<PathBuf> {
+        let dir = std::env::temp_dir().join(format!("bullhorn_{}_{}", name, std::process::id()));
+        git::Repository::init(&dir)?;
+        Ok(dir)
+    }
+
+    #[cfg(feature = "github_pages")]
+    #[tokio::test]
+    async fn publish() -> Result<()> {
+        let dir = repo("publish")?;
+        let post = "---\ntitle: title\ncanonical_url: https://user.github.io/a.html\n---\nbody";
+        std::fs::write(dir.join("2021-05-03-a.md"), post)?;
+        std::fs::write(dir.join("2021-05-04-b.md"), "No front matter")?;
+        let platforms: Vec<Box<dyn Platform>> = vec![Box::new(platforms::tests::Mock::default())];
+        let files = vec![
+            dir.join("2021-05-04-b.md").display().to_string(),
+            dir.join("2021-05-03-a.md").display().to_string(),
+        ];
+
+        // Post that can't be opened fails without stopping the others
+        let summary = publish_to(&platforms, &Settings::default(), &files).await?;
+        let statuses: Vec<&Status> = summary
+            .entries
+            .iter()
+            .map(|entry| &entry.outcome.status)
+            .collect();
+        assert_eq!(statuses, vec![&Status::Failed, &Status::Created]);
+        assert!(!summary.is_success());
+        let state = std::fs::read_to_string(dir.join(state::DEFAULT_PATH))?;
+        assert!(state.contains("2021-05-03-a.md"));
+        std::fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+
+    #[cfg(feature = "github_pages")]
+    #[tokio::test]
+    async fn publish_written_back() -> Result<()> {
+        let dir = repo("written_back")?;
+        let path = dir.join("2021-05-03-a.md");
+        let post = "---\ntitle: title\ncanonical_url: https://user.github.io/a.html\n---\nbody";
+        std::fs::write(&path, post)?;
+        let mock = platforms::tests::Mock::default();
+        let platforms: Vec<Box<dyn Platform>> = vec![Box::new(mock)];
+        let settings = Settings {
+            write_back: true,
+            ..Default::default()
+        };
+        let files = vec![path.display().to_string()];
+
+        let summary = publish_to(&platforms, &settings, &files).await?;
+        assert_eq!(summary.entries[0].outcome.status, Status::Created);
+        assert!(std::fs::read_to_string(&path)?.contains("bullhorn:"));
+
+        // Front matter written back isn't a change to the post
+        let summary = publish_to(&platforms, &settings, &files).await?;
+        assert_eq!(summary.entries[0].outcome.status, Status::Skipped);
+        std::fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn orphans() {
+        let article = |id: &str, url: &str| ListedArticle {
+            id: id.to_owned(),
+            canonical_url: Some(url.to_owned()),
+            ..Default::default()
+        };
+        let articles = vec![
+            article("0", "https://user.github.io/2021/05/03/post.html"),
+            article("1", "https://user.github.io/2021/05/04/deleted.html"),
+            article("2", "https://elsewhere.com/2021/05/04/other.html"),
+            ListedArticle::default(),
+        ];
+        let canonical_urls = vec!["https://user.github.io/2021/05/03/post.html".to_owned()]
+            .into_iter()
+            .collect();
+        let orphans: Vec<&str> = find_orphans(&articles, &canonical_urls)
+            .iter()
+            .map(|article| article.id.as_str())
+            .collect();
+        assert_eq!(orphans, vec!["1"]);
+        assert_eq!(
+            site("https://user.github.io"),
+            Some("https://user.github.io")
+        );
+    }
+
+    #[test]
+    fn write_back() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("bullhorn_write_{}", std::process::id()));
+        std::fs::create_dir_all(&dir)?;
+        let path = dir.join("2021-05-03-post.md");
+        let source = "---\ntitle: title # comment\ntags: [a, b]\nauthor: author\n---\nbody";
+        std::fs::write(&path, source)?;
+        let mut post = Post::open(path.clone())?;
+        post.front_matter.published = Some(false);
+        post.front_matter.canonical_url = Some("https://user.github.io/post.html".to_owned());
+        post.front_matter.date = Some(post::Date::parse("2021-05-03")?);
+        let outcome = |platform, status| PublishOutcome {
+            platform,
+            status,
+            remote_id: Some("1234".to_owned()),
+            remote_url: Some("https://dev.to/user/title".to_owned()),
+            error: None,
+            preview: None,
+        };
+
+        super::write_back(&post, &[outcome("devto", Status::Created)])?;
+        let text = std::fs::read_to_string(&path)?;
+        assert_eq!(
+            text,
+            "---
+title: title # comment
+tags: [a, b]
+author: author
+canonical_url: \"https://user.github.io/post.html\"
+date: 2021-05-03
+bullhorn:
+  devto:
+    id: \"1234\"
+    url: \"https://dev.to/user/title\"
+---
+body"
+        );
+        let post = Post::open(path.clone())?;
+        assert_eq!(
+            post.front_matter.bullhorn["devto"].url.as_deref(),
+            Some("https://dev.to/user/title")
+        );
+
+        super::write_back(&post, &[outcome("devto", Status::Deleted)])?;
+        let text = std::fs::read_to_string(&path)?;
+        assert!(text.starts_with("---\ntitle: title # comment\ntags: [a, b]\n"));
+        assert!(!text.contains("bullhorn"));
+        std::fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn posts() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("bullhorn_posts_{}", std::process::id()));
+        for file in &[
+            "_posts/2021-05-03-a.md",
+            "_posts/2021/2021-05-04-b.markdown",
+            "_posts/image.png",
+            "_drafts/c.md",
+        ] {
+            let path = dir.join(file);
+            std::fs::create_dir_all(path.parent().unwrap())?;
+            std::fs::write(path, "---\ntitle: Title\n---\n")?;
+        }
+        // Skipped when walking directories
+        std::fs::write(dir.join("README.md"), "# Blog\n")?;
+        let to_names = |paths: Vec<PathBuf>| -> Vec<String> {
+            paths
+                .iter()
+                .map(|path| path.strip_prefix(&dir).unwrap().display().to_string())
+                .collect()
+        };
+        let dir_str = dir.display().to_string();
+
+        let found = find_posts(&[dir_str.clone()], &Settings::default())?;
+        assert_eq!(
+            to_names(found),
+            vec![
+                "_drafts/c.md",
+                "_posts/2021/2021-05-04-b.markdown",
+                "_posts/2021-05-03-a.md"
+            ]
+        );
+
+        let settings = Settings {
+            exclude: vec!["_drafts/".to_owned()],
+            ..Default::default()
+        };
+        let found = find_posts(&[dir_str.clone()], &settings)?;
+        assert_eq!(
+            to_names(found),
+            vec![
+                "_posts/2021/2021-05-04-b.markdown",
+                "_posts/2021-05-03-a.md"
+            ]
+        );
+
+        let settings = Settings {
+            include: vec!["*.md".to_owned()],
+            ..Default::default()
+        };
+        let pattern = format!("{}/_posts/*", dir_str);
+        let found = find_posts(
+            &[pattern, dir.join("_drafts").display().to_string()],
+            &settings,
+        )?;
+        assert_eq!(
+            to_names(found),
+            vec!["_posts/2021-05-03-a.md", "_drafts/c.md"]
+        );
+
+        // Unless given explicitly
+        let found = find_posts(
+            &[dir.join("README.md").display().to_string()],
+            &Settings::default(),
+        )?;
+        assert_eq!(to_names(found), vec!["README.md"]);
+
+        std::fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+}
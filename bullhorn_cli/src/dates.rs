@@ -0,0 +1,106 @@
+use crate::*;
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, TimeZone, Utc};
+
+/// Formats with a time zone, e.g. Jekyll's `2021-05-03 10:00:00 +0900`
+const ZONED_FORMATS: &[&str] = &[
+    "%Y-%m-%d %H:%M:%S%.f %z",
+    "%Y-%m-%d %H:%M %z",
+    "%Y-%m-%dT%H:%M:%S%.f%z",
+];
+
+/// Formats without a time zone, which are UTC
+const NAIVE_FORMATS: &[&str] = &[
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M",
+];
+
+/// Parse a date from front matter or the command line, e.g. `2021-05-03`,
+/// `2021-05-03 10:00:00 +0900` (Jekyll), or `2021-05-03T10:00:00+09:00` (Hugo)
+pub fn parse(text: &str) -> Result<DateTime<FixedOffset>> {
+    let text = text.trim();
+    if let Ok(date) = DateTime::parse_from_rfc3339(text) {
+        return Ok(date);
+    }
+    if let Some(date) = ZONED_FORMATS
+        .iter()
+        .find_map(|format| DateTime::parse_from_str(text, format).ok())
+    {
+        return Ok(date);
+    }
+
+    let naive_text = text.trim_end_matches(" UTC").trim_end_matches(" GMT");
+    let naive = NAIVE_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(naive_text, format).ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(naive_text, "%Y-%m-%d")
+                .ok()?
+                .and_hms_opt(0, 0, 0)
+        });
+    match naive {
+        Some(naive) => Ok(Utc.from_utc_datetime(&naive).into()),
+        None => Err(Error::BadString {
+            expected: "date (e.g. `2021-05-03 10:00:00 +0900`)".to_owned(),
+            found: text.to_owned(),
+        }
+        .into()),
+    }
+}
+
+/// ISO-8601, e.g. `2021-05-03T10:00:00+09:00` (dev.to, Tumblr `publish_on`)
+pub fn to_iso8601(date: &DateTime<FixedOffset>) -> String {
+    date.to_rfc3339()
+}
+
+/// GMT, e.g. `2021-05-03 01:00:00 GMT` (Tumblr legacy API)
+pub fn to_gmt(date: &DateTime<FixedOffset>) -> String {
+    date.with_timezone(&Utc)
+        .format("%Y-%m-%d %H:%M:%S GMT")
+        .to_string()
+}
+
+/// Jekyll front matter, e.g. `2021-05-03 10:00:00 +0900`
+pub fn to_front_matter(date: &DateTime<FixedOffset>) -> String {
+    date.format("%Y-%m-%d %H:%M:%S %z").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_formats() -> Result<()> {
+        let utc = |text: &str| -> Result<String> { Ok(to_gmt(&parse(text)?)) };
+        assert_eq!(utc("2021-05-03")?, "2021-05-03 00:00:00 GMT");
+        assert_eq!(utc("2021-05-03 10:00")?, "2021-05-03 10:00:00 GMT");
+        assert_eq!(utc("2021-05-03 10:00:00")?, "2021-05-03 10:00:00 GMT");
+        assert_eq!(utc("2021-05-03 10:00:00 UTC")?, "2021-05-03 10:00:00 GMT");
+        assert_eq!(utc("2021-05-03 10:00:00 +0900")?, "2021-05-03 01:00:00 GMT");
+        assert_eq!(utc("2021-05-03 10:00 -0100")?, "2021-05-03 11:00:00 GMT");
+        assert_eq!(utc("2021-05-03T10:00:00+09:00")?, "2021-05-03 01:00:00 GMT");
+        assert_eq!(utc("2021-05-03T10:00:00.5Z")?, "2021-05-03 10:00:00 GMT");
+        assert_eq!(utc("2021-05-03T10:00:00")?, "2021-05-03 10:00:00 GMT");
+
+        for text in &[
+            "",
+            "tomorrow",
+            "2021-13-01",
+            "2021-05-03 25:00",
+            "03/05/2021",
+        ] {
+            let _ = parse(text).unwrap_err();
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn format() -> Result<()> {
+        let date = parse("2021-05-03 10:00:00 +0900")?;
+        assert_eq!(to_iso8601(&date), "2021-05-03T10:00:00+09:00");
+        assert_eq!(to_front_matter(&date), "2021-05-03 10:00:00 +0900");
+        assert_eq!(parse(&to_front_matter(&date))?, date);
+        Ok(())
+    }
+}
@@ -2,11 +2,14 @@ use anyhow::{Context, Result};
 use clap::Clap;
 use tracing::{debug, error, info, trace, warn};
 
+mod commands;
+mod dates;
 mod platforms;
 mod post;
+mod report;
 mod settings;
+mod state;
 
-use platforms::*;
 use post::Post;
 use settings::*;
 
@@ -25,15 +28,23 @@ pub enum Error {
     BadString { expected: String, found: String },
     #[error("Not found: {expected}")]
     NotFound { expected: String },
+    #[error("Already exists: {expected} (id={id})")]
+    Exists { expected: String, id: String },
     #[error("Bad format: {thing}")]
     BadFormat { thing: String },
+    #[error("Stopped searching after {pages} page(s), increase --max-pages to search further")]
+    PageLimit { pages: u32 },
+    #[error("Unsupported by {platform}: {operation}")]
+    Unsupported { platform: String, operation: String },
     #[error("Failed")]
     Failed,
 }
 
-async fn start() -> Result<()> {
+async fn start() -> Result<report::Summary> {
     tracing_subscriber::fmt()
         .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        // Keep stdout for the report
+        .with_writer(std::io::stderr)
         .init();
 
     let mut opts = Opts::parse();
@@ -46,132 +57,56 @@ async fn start() -> Result<()> {
         ];
     }
 
-    if let Ok(config) = shellexpand::env(&opts.settings.config) {
-        use std::io::prelude::*;
-
-        let mut buffer = String::new();
-        let _size = std::fs::File::open(std::path::PathBuf::from(config.to_string()))?
-            .read_to_string(&mut buffer)?;
-        process_config(&mut opts, &buffer)?;
-    } else {
-        error!("Unable to expand config: {}", opts.settings.config);
+    match config_path(&opts.settings) {
+        // Config file is created by `auth` if it doesn't exist yet
+        Ok(config) if !config.exists() => debug!("No config: {}", config.display()),
+        Ok(config) => {
+            let buffer = std::fs::read_to_string(config)?;
+            process_config(&mut opts, &buffer)?;
+        }
+        Err(err) => error!("{}", err),
     }
 
-    for file in &opts.settings.posts {
-        let path = std::path::PathBuf::from(file);
-        if !path.is_file() {
-            warn!("File not found, skipping: {}", file);
-            continue;
+    if let Some(Command::Delete(_)) = opts.command {
+        opts.settings.operation = Operation::Delete;
+    }
+    let summary = match &opts.command {
+        Some(Command::Publish(args)) | Some(Command::Delete(args)) => {
+            commands::publish(&opts, &args.posts).await?
         }
-        let mut post = Post::open(path)?;
-        post.apply(&opts.settings);
-
-        // Post "original" represented by canonical URL
-        #[cfg(feature = "github_pages")]
-        {
-            let git = github_pages::GithubPages::new(&post, opts.settings.clone())?;
-            git.publish(&mut post)?;
+        Some(Command::Auth(args)) => {
+            platforms::auth(&opts, &args.platform).await?;
+            report::Summary::default()
         }
-
-        let mut futures: Vec<futures::future::LocalBoxFuture<()>> = vec![];
-
-        #[cfg(feature = "devto")]
-        if let (Some(_), Some(api_token)) = (
-            opts.platforms.iter().find(|p| **p == Platforms::Devto),
-            &opts.devto_api_token,
-        ) {
-            let settings = opts.settings.clone();
-            let post = post.clone();
-            futures.push(Box::pin(async move {
-                let devto = devto::Devto::new(api_token.clone(), settings);
-                devto.try_publish(post).await
-            }));
+        Some(Command::List(args)) => {
+            commands::list(&opts, &args.platform).await?;
+            report::Summary::default()
         }
-
-        #[cfg(feature = "hashnode")]
-        if let (Some(_), Some(api_token), Some(username)) = (
-            opts.platforms.iter().find(|p| **p == Platforms::Hashnode),
-            &opts.hashnode_api_token,
-            &opts.hashnode_username,
-        ) {
-            let settings = opts.settings.clone();
-            let post = post.clone();
-            futures.push(Box::pin(async move {
-                let hashnode =
-                    hashnode::Hashnode::new(api_token.clone(), username.clone(), settings);
-                hashnode.try_publish(post).await
-            }));
+        Some(Command::Status(args)) => {
+            commands::status(&opts, &args.posts).await?;
+            report::Summary::default()
         }
-
-        #[cfg(feature = "medium")]
-        if let (Some(_), Some(api_token)) = (
-            opts.platforms.iter().find(|p| **p == Platforms::Medium),
-            &opts.medium_api_token,
-        ) {
-            let settings = opts.settings.clone();
-            let pub_id = opts.medium_publication_id.clone();
-            let post = post.clone();
-            futures.push(Box::pin(async move {
-                let medium = medium::Medium::new(api_token.clone(), pub_id, settings);
-                medium.try_publish(post).await
-            }));
+        Some(Command::Validate(args)) => {
+            commands::validate(&opts, &args.posts)?;
+            report::Summary::default()
         }
-
-        // #[cfg(feature = "tumblr")]
-        // if let (Some(_), Some(consumer_key), Some(consumer_secret)) = (
-        //     opts.platforms.iter().find(|p| **p == Platforms::Tumblr),
-        //     &opts.tumblr_consumer_key,
-        //     &opts.tumblr_consumer_secret,
-        // ) {
-        //     let settings = opts.settings.clone();
-        //     let post = post.clone();
-        //     futures.push(Box::pin(async move {
-        //         let medium =
-        //             tumblr::Auth::new(consumer_key.clone(), consumer_secret.clone(), settings);
-        //         medium.try_auth().await
-        //     }));
-        // }
-
-        #[cfg(feature = "tumblr")]
-        if let (
-            Some(_),
-            Some(consumer_key),
-            Some(consumer_secret),
-            Some(token),
-            Some(token_secret),
-            Some(blog_id),
-        ) = (
-            opts.platforms.iter().find(|p| **p == Platforms::Tumblr),
-            &opts.tumblr_consumer_key,
-            &opts.tumblr_consumer_secret,
-            &opts.tumblr_token,
-            &opts.tumblr_token_secret,
-            &opts.tumblr_blog_id,
-        ) {
-            let settings = opts.settings.clone();
-            let post = post.clone();
-            futures.push(Box::pin(async move {
-                let medium = tumblr::Tumblr::new(
-                    consumer_key.clone(),
-                    consumer_secret.clone(),
-                    token.clone(),
-                    token_secret.clone(),
-                    blog_id.clone(),
-                    settings,
-                );
-                medium.try_publish(post).await
-            }));
+        Some(Command::Config) => {
+            commands::config(&opts)?;
+            report::Summary::default()
         }
-
-        futures::future::join_all(futures).await;
-    }
-
-    Ok(())
+        // Posts without a subcommand are published, as before subcommands were added
+        None => commands::publish(&opts, &opts.posts).await?,
+    };
+    Ok(summary)
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    start().await
+    let summary = start().await?;
+    if !summary.is_success() {
+        std::process::exit(1);
+    }
+    Ok(())
 }
 
 #[cfg(test)]
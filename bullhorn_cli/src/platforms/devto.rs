@@ -1,20 +1,32 @@
 #![cfg(feature = "devto")]
 
 /// https://docs.forem.com/api
-use crate::{post::Post, *};
+use crate::{platforms::*, post::Post, *};
 
 type ArticleResponse = serde_json::Map<String, serde_json::Value>;
 const URL: &str = "https://dev.to/api";
+/// Articles per page when listing articles (maximum 1000)
+const PER_PAGE: usize = 100;
 
-#[derive(serde::Serialize)]
+#[derive(Default, serde::Serialize)]
 struct Article {
-    title: String,
-    body_markdown: String,
-    published: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    body_markdown: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    published: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     canonical_url: Option<String>,
-    #[serde(skip_serializing_if = "Vec::is_empty")]
-    tags: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    main_image: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tags: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     series: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     date: Option<String>,
 }
 
@@ -26,28 +38,72 @@ struct Body {
 impl From<Post> for Body {
     fn from(item: Post) -> Self {
         let published = item.front_matter.is_published();
+        let date = item.front_matter.parsed_date();
 
-        let tags = item.front_matter.tags.unwrap_or_default();
+        let tags = normalize_tags("devto", &item.front_matter.all_tags(), normalize_tag);
         let num_tags = tags.len();
         // Must limit to 4 tags otherwise devto returns 422: "Tag list exceed the maximum of 4 tags"
         const MAX_TAGS: usize = 4;
-        let tags = tags.into_iter().take(MAX_TAGS).collect();
+        let tags: Vec<String> = tags.into_iter().take(MAX_TAGS).collect();
         if num_tags > MAX_TAGS {
             warn!("Limited to {} tags, reduced to: {:?}", MAX_TAGS, tags);
         }
         let article = Article {
-            title: item.front_matter.title.clone(),
-            body_markdown: item.body,
-            published,
+            title: Some(item.front_matter.title.clone()),
+            body_markdown: Some(item.body),
+            published: Some(published),
             canonical_url: item.front_matter.canonical_url,
-            tags,
+            description: item.front_matter.description,
+            main_image: item.front_matter.cover_image,
+            tags: Some(tags).filter(|tags| !tags.is_empty()),
             series: item.front_matter.series,
-            date: item.front_matter.date,
+            date: date.as_ref().map(dates::to_iso8601),
         };
         Body { article }
     }
 }
 
+impl Body {
+    /// Body of PUT request that only changes fields selected by `Settings::update_fields`.
+    /// Devto leaves fields that aren't sent unchanged.
+    fn for_update(item: Post, settings: &Settings) -> Self {
+        let mut body: Body = item.into();
+        let article = &mut body.article;
+        if !settings.is_updated(UpdateField::Title) {
+            article.title = None;
+        }
+        if !settings.is_updated(UpdateField::Body) {
+            article.body_markdown = None;
+        }
+        if !settings.is_updated(UpdateField::Published) {
+            article.published = None;
+            article.date = None;
+        }
+        if !settings.is_updated(UpdateField::CanonicalUrl) {
+            article.canonical_url = None;
+        }
+        if !settings.is_updated(UpdateField::Description) {
+            article.description = None;
+        }
+        if !settings.is_updated(UpdateField::CoverImage) {
+            article.main_image = None;
+        }
+        if !settings.is_updated(UpdateField::Tags) {
+            article.tags = None;
+        }
+        if !settings.is_updated(UpdateField::Series) {
+            article.series = None;
+        }
+        if settings.update_fields.contains(&UpdateField::Slug) {
+            debug!(
+                "Devto generates slug from title, ignoring: {:?}",
+                UpdateField::Slug
+            );
+        }
+        body
+    }
+}
+
 pub struct Devto {
     settings: Settings,
     api_token: String,
@@ -65,17 +121,49 @@ impl Devto {
         }
     }
 
-    fn compare(&self, article: &ArticleResponse, value: &String) -> bool {
-        let resp_field = match self.settings.compare {
-            Compare::CanonicalUrl => "canonical_url",
-            Compare::Slug => panic!(),
+    pub fn from_opts(opts: &Opts) -> Option<Self> {
+        let api_token = opts.devto_api_token.as_ref()?;
+        Some(Self::new(api_token.clone(), opts.settings.clone()))
+    }
+
+    fn compare(&self, article: &ArticleResponse, post: &Post) -> bool {
+        let get_field = |field| match article.get(field) {
+            Some(serde_json::Value::String(value)) => Some(value.as_str()),
+            _ => None,
         };
-        match article.get(resp_field) {
-            Some(serde_json::Value::String(resp_url)) => resp_url == value,
-            _ => false,
+        match self.settings.compare {
+            Compare::CanonicalUrl => {
+                get_field("canonical_url").is_some()
+                    && get_field("canonical_url") == post.front_matter.canonical_url.as_deref()
+            }
+            Compare::Slug => get_field("slug").map_or(false, |resp_slug| {
+                // Devto generates slug from title rather than using front-matter slug
+                let title_slug = slug::slugify(&post.front_matter.title);
+                post.front_matter
+                    .slug
+                    .iter()
+                    .chain(std::iter::once(&title_slug))
+                    .any(|slug| is_slug_match(resp_slug, slug))
+            }),
         }
     }
 
+    /// Page of the user's articles, both published and unpublished
+    async fn get_articles(&self, page: u32) -> Result<Vec<ArticleResponse>> {
+        let articles = self
+            .client
+            .get(format!("{}/articles/me/all", URL))
+            // Pages start at 1
+            .query(&[("page", page + 1), ("per_page", PER_PAGE as u32)])
+            .auth(self)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(articles)
+    }
+
     fn get_id(&self, article: &ArticleResponse) -> Option<String> {
         match article.get("id") {
             Some(serde_json::Value::String(id)) => Some(id.clone()),
@@ -84,76 +172,168 @@ impl Devto {
         }
     }
 
-    pub async fn try_publish(&self, post: Post) {
-        if let Err(err) = self.publish(post).await {
-            error!("Failed: {}", err);
+    fn to_article(&self, article: &ArticleResponse) -> Option<ListedArticle> {
+        let get_field = |field| {
+            article
+                .get(field)
+                .and_then(|value| value.as_str())
+                .map(String::from)
+        };
+        self.get_id(article).map(|id| ListedArticle {
+            platform: self.name(),
+            id,
+            url: get_field("url"),
+            title: get_field("title"),
+            slug: get_field("slug"),
+            canonical_url: get_field("canonical_url"),
+            published: article.get("published").and_then(|value| value.as_bool()),
+        })
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Platform for Devto {
+    fn name(&self) -> &'static str {
+        "devto"
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            update: true,
+            delete: true,
+            schedule: false,
         }
     }
 
-    async fn publish(&self, post: Post) -> Result<()> {
-        let compare_val = match self.settings.compare {
-            Compare::CanonicalUrl => &post.front_matter.canonical_url,
-            Compare::Slug => panic!("Not supported"),
-        };
-        let existing_id = if let Some(compare_val) = compare_val {
-            let me_articles = self
-                .client
-                .get(format!("{}/articles/me", URL))
-                .auth(self)
-                .send()
-                .await;
-            if let Ok(me_articles) = me_articles {
-                let me_articles = me_articles.json::<Vec<ArticleResponse>>().await?;
-                me_articles
+    async fn find_existing(&self, post: &Post) -> Result<Option<RemoteArticle>> {
+        if self.settings.compare == Compare::CanonicalUrl
+            && post.front_matter.canonical_url.is_none()
+        {
+            warn!("No canonical URL");
+            return Ok(None);
+        }
+        let existing = find_in_pages(
+            &self.settings,
+            Some(PER_PAGE),
+            |page| self.get_articles(page),
+            |articles: &[ArticleResponse]| {
+                articles
                     .iter()
-                    .find(|a| self.compare(a, compare_val))
-                    .and_then(|a| self.get_id(&a))
-            } else {
-                None
-            }
-        } else {
-            None
-        };
+                    .filter(|a| self.compare(a, post))
+                    .find_map(|a| self.to_article(a))
+                    .map(|a| RemoteArticle::from(&a))
+            },
+        )
+        .await?;
+        Ok(existing)
+    }
 
-        if let Some(ref existing_id) = existing_id {
-            info!(
-                "Matched existing article: id={} ({:?})",
-                existing_id, compare_val
-            );
+    async fn create(&self, post: &Post) -> Result<RemoteArticle> {
+        let body: Body = post.clone().into();
+        let resp: Response = self
+            .client
+            .post(format!("{}/articles", URL))
+            .auth(self)
+            .json(&body)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        debug!("{:?}", resp);
+        Ok(resp.into())
+    }
+
+    async fn update(&self, existing: &RemoteArticle, post: &Post) -> Result<RemoteArticle> {
+        let body = Body::for_update(post.clone(), &self.settings);
+        let resp: Response = self
+            .client
+            .put(format!("{}/articles/{}", URL, existing.id))
+            .auth(self)
+            .json(&body)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        debug!("{:?}", resp);
+        Ok(resp.into())
+    }
+
+    /// Devto API can't delete articles, unpublish instead
+    async fn delete(&self, existing: &RemoteArticle) -> Result<()> {
+        let body = serde_json::json!({ "article": { "published": false } });
+        let resp: Response = self
+            .client
+            .put(format!("{}/articles/{}", URL, existing.id))
+            .auth(self)
+            .json(&body)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        debug!("{:?}", resp);
+        Ok(())
+    }
+
+    async fn list(&self) -> Result<Vec<ListedArticle>> {
+        let articles = collect_pages(&self.settings, Some(PER_PAGE), |page| {
+            self.get_articles(page)
+        })
+        .await?;
+        Ok(articles.iter().filter_map(|a| self.to_article(a)).collect())
+    }
+
+    async fn get_content(&self, existing: &RemoteArticle) -> Result<Option<Content>> {
+        let resp: Response = self
+            .client
+            .get(format!("{}/articles/{}", URL, existing.id))
+            .auth(self)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(Some(Content {
+            title: resp.title,
+            tags: resp.tags,
+            body: resp.body_markdown.unwrap_or_default(),
+        }))
+    }
+
+    fn to_content(&self, post: &Post) -> Content {
+        let body: Body = post.clone().into();
+        Content {
+            tags: body.article.tags.unwrap_or_default(),
+            ..post.into()
         }
+    }
+}
 
-        let body: Body = post.into();
-        if self.settings.dry {
-            self.client
-                .get(format!("{}/articles/me", URL))
-                .query(&[("per_page", "1")])
-                .auth(self)
-                .send()
-                .await?;
-        } else if let Some(existing_id) = existing_id {
-            let resp: Response = self
-                .client
-                .put(format!("{}/articles/{}", URL, existing_id))
-                .auth(self)
-                .json(&body)
-                .send()
-                .await?
-                .json()
-                .await?;
-            debug!("{:?}", resp);
-        } else {
-            let resp = self
-                .client
-                .post(format!("{}/articles", URL))
-                .auth(self)
-                .json(&body)
-                .send()
-                .await?
-                .json()
-                .await?;
-            debug!("{:?}", resp);
+/// Devto tags may only contain lowercase letters and numbers
+fn normalize_tag(tag: &str) -> String {
+    tag.chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+/// Devto appends a random suffix to slugs (e.g. `title-39h7`), ignore it when comparing
+fn is_slug_match(resp_slug: &str, slug: &str) -> bool {
+    if resp_slug == slug {
+        return true;
+    }
+    match resp_slug
+        .strip_prefix(slug)
+        .and_then(|rest| rest.strip_prefix('-'))
+    {
+        Some(suffix) => {
+            !suffix.is_empty()
+                && suffix.len() <= 4
+                && suffix.chars().all(|c| c.is_ascii_alphanumeric())
         }
-        Ok(())
+        None => false,
     }
 }
 
@@ -171,13 +351,78 @@ struct Response {
     description: String,
     slug: String,
     path: String,
+    url: String,
     canonical_url: String,
+    /// Only returned when getting a single article
+    #[serde(default)]
+    body_markdown: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+impl From<Response> for RemoteArticle {
+    fn from(item: Response) -> Self {
+        Self {
+            id: item.id.to_string(),
+            url: Some(item.url),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn update_fields() -> Result<()> {
+        let post = Post::new("---\ntitle: title\ntags: [tag0]\n---\nbody")?;
+        let body = serde_json::to_value(Body::for_update(post.clone(), &Settings::default()))?;
+        assert_eq!(body["article"]["title"], "title");
+        assert_eq!(body["article"]["body_markdown"], "body");
+        assert_eq!(body["article"]["tags"][0], "tag0");
+
+        let settings = Settings {
+            update_fields: vec![UpdateField::Body],
+            ..Default::default()
+        };
+        let body = serde_json::to_value(Body::for_update(post, &settings))?;
+        let article = body["article"].as_object().unwrap();
+        assert_eq!(article.len(), 1);
+        assert_eq!(article["body_markdown"], "body");
+        Ok(())
+    }
+
+    #[test]
+    fn slug_match() {
+        let slug = "native-code-in-net-5-0-and-c-9-0";
+        assert!(is_slug_match(slug, slug));
+        assert!(is_slug_match("native-code-in-net-5-0-and-c-9-0-39h7", slug));
+        assert!(!is_slug_match(
+            "native-code-in-net-5-0-and-c-9-0-part-2",
+            slug
+        ));
+        assert!(!is_slug_match("native-code-in-net-5-0-and-c-9-0-", slug));
+        assert!(!is_slug_match("native-code-in-net-5-0", slug));
+    }
+
+    #[test]
+    fn tags() -> Result<()> {
+        let post = Post::new(
+            "---\ntitle: title\ntags: [Rust, C++, rust, dotnet-core]\ncategories: [\"#\", a, b]\n---\nbody",
+        )?;
+        let body: Body = post.into();
+        assert_eq!(
+            body.article.tags,
+            Some(vec![
+                "rust".to_owned(),
+                "c".to_owned(),
+                "dotnetcore".to_owned(),
+                "a".to_owned()
+            ])
+        );
+        Ok(())
+    }
+
     #[test]
     fn response() -> Result<()> {
         let path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
@@ -1,6 +1,7 @@
 #![cfg(feature = "github_pages")]
 
 use crate::*;
+use std::path::{Path, PathBuf};
 
 #[derive(serde::Serialize)]
 struct Article {
@@ -24,8 +25,13 @@ pub struct GithubPages {
 
 impl GithubPages {
     pub fn new(post: &Post, settings: Settings) -> Result<Self> {
-        debug!("git: Searching for repository: {:?}", post.path);
-        let mut repo_path = post.path.parent();
+        Self::open(&post.path, settings)
+    }
+
+    /// Open the repository containing `path`
+    pub fn open(path: &Path, settings: Settings) -> Result<Self> {
+        debug!("git: Searching for repository: {:?}", path);
+        let mut repo_path = path.parent();
         let (repo, repo_path) = loop {
             match repo_path {
                 Some(path) => match git::Repository::open(&path) {
@@ -34,7 +40,7 @@ impl GithubPages {
                 },
                 None => {
                     return Err(Error::NotFound {
-                        expected: format!("Git repository for: {:?}", &post.path),
+                        expected: format!("Git repository for: {:?}", path),
                     }
                     .into())
                 }
@@ -57,14 +63,53 @@ impl GithubPages {
 
         if post.front_matter.date.is_none() {
             // 2021-05-03 00:00:00 UTC
-            let date = format!("{}-{:02}-{:02}", parts.year, parts.month, parts.day);
+            let date = chrono::NaiveDate::from_ymd_opt(parts.year as i32, parts.month, parts.day)
+                .ok_or_else(|| Error::BadPath {
+                expected: "YYYY-MM-DD-name.ext with a valid date".to_owned(),
+                found: post.path.clone(),
+            })?;
+            let date = date.format("%Y-%m-%d").to_string();
             debug!("Setting date: {} ({:?})", date, post.path);
-            post.front_matter.date = Some(date);
+            post.front_matter.date = Some(post::Date::parse(&date)?);
         }
 
         Ok(())
     }
 
+    /// Root of the repository's working directory
+    pub fn workdir(&self) -> Option<&std::path::Path> {
+        self.repo.workdir()
+    }
+
+    /// Files added or modified between `rev` and the working tree, relative to `workdir()`
+    pub fn changed_since(&self, rev: &str) -> Result<Vec<PathBuf>> {
+        let tree = self
+            .repo
+            .revparse_single(rev)
+            .with_context(|| format!("Unable to find git revision: {}", rev))?
+            .peel_to_tree()?;
+        let mut options = git::DiffOptions::new();
+        options.include_untracked(true).recurse_untracked_dirs(true);
+        let diff = self
+            .repo
+            .diff_tree_to_workdir_with_index(Some(&tree), Some(&mut options))?;
+        let changed = diff
+            .deltas()
+            .filter(|delta| {
+                matches!(
+                    delta.status(),
+                    git::Delta::Added
+                        | git::Delta::Modified
+                        | git::Delta::Renamed
+                        | git::Delta::Copied
+                        | git::Delta::Untracked
+                )
+            })
+            .filter_map(|delta| delta.new_file().path().map(Path::to_owned))
+            .collect();
+        Ok(changed)
+    }
+
     fn get_canonical_url(&self, parts: &FilenameParts) -> Result<String> {
         // Obtain server from git remote.  E.g.
         // `origin	github:repo/repo.github.io.git` -> `repo.github.io`
@@ -122,7 +167,6 @@ impl GithubPages {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::path::PathBuf;
 
     fn create_post(filename: &str) -> Post {
         Post {
@@ -183,4 +227,26 @@ mod tests {
         //assert_eq!(canonical_url, "https://cargo_bullhorn.github.io/2021/07/01/test.html");
         Ok(())
     }
+
+    #[test]
+    fn changed_since() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("bullhorn_git_{}", std::process::id()));
+        let repo = git::Repository::init(&dir)?;
+        std::fs::write(dir.join("old.md"), "")?;
+        let mut index = repo.index()?;
+        index.add_path(Path::new("old.md"))?;
+        let tree = repo.find_tree(index.write_tree()?)?;
+        let signature = git::Signature::now("test", "test@example.com")?;
+        repo.commit(Some("HEAD"), &signature, &signature, "old", &tree, &[])?;
+        std::fs::write(dir.join("new.md"), "")?;
+
+        let github_pages = GithubPages::open(&dir.join("new.md"), Default::default())?;
+        assert_eq!(
+            github_pages.changed_since("HEAD")?,
+            vec![PathBuf::from("new.md")]
+        );
+        let _ = github_pages.changed_since("no-such-branch").unwrap_err();
+        std::fs::remove_dir_all(dir)?;
+        Ok(())
+    }
 }
@@ -1,7 +1,7 @@
 #![cfg(feature = "hashnode")]
 
 /// https://api.hashnode.com/
-use crate::{post::Post, *};
+use crate::{platforms::*, post::Post, *};
 use graphql_client::GraphQLQuery;
 
 const URL: &str = "https://api.hashnode.com/";
@@ -22,6 +22,14 @@ pub struct Tags;
 )]
 pub struct PubPosts;
 
+#[derive(graphql_client::GraphQLQuery)]
+#[graphql(
+    schema_path = "hashnode_schema.json",
+    query_path = "src/hashnode.graphql",
+    response_derives = "Debug"
+)]
+pub struct PostDetails;
+
 #[derive(graphql_client::GraphQLQuery)]
 #[graphql(
     schema_path = "hashnode_schema.json",
@@ -40,6 +48,14 @@ pub struct CreatePubStory;
 )]
 pub struct UpdateStory;
 
+#[derive(graphql_client::GraphQLQuery)]
+#[graphql(
+    schema_path = "hashnode_schema.json",
+    query_path = "src/hashnode.graphql",
+    response_derives = "Debug"
+)]
+pub struct DeletePost;
+
 pub struct Hashnode {
     settings: Settings,
     api_token: String,
@@ -59,9 +75,20 @@ impl Hashnode {
         }
     }
 
+    pub fn from_opts(opts: &Opts) -> Option<Self> {
+        let api_token = opts.hashnode_api_token.as_ref()?;
+        let username = opts.hashnode_username.as_ref()?;
+        Some(Self::new(
+            api_token.clone(),
+            username.clone(),
+            opts.settings.clone(),
+        ))
+    }
+
     async fn get_tag_ids(&self, post: &Post) -> Result<Vec<String>> {
         let mut tags: Vec<String> = vec![];
-        if let Some(front_matter_tags) = &post.front_matter.tags {
+        let front_matter_tags = post.front_matter.all_tags();
+        if !front_matter_tags.is_empty() {
             // Get all hashnode tags
             let body = Tags::build_query(tags::Variables);
             let resp = self.client.post(URL).json(&body).send().await?;
@@ -79,7 +106,7 @@ impl Hashnode {
                     .collect()
             };
 
-            for tag in front_matter_tags {
+            for tag in &front_matter_tags {
                 // Find hashnode tag that matches front-matter tag
                 let slug = slug::slugify(&tag);
                 if let Some(tag_match) = categories.iter().find(|category| {
@@ -110,158 +137,380 @@ impl Hashnode {
                             }
                         }
                     }
-                    trace!("Unable to match tag: {}", tag);
+                    // Hashnode only accepts its own tags
+                    warn!(
+                        "hashnode: Dropping tag `{}`, no matching tag ({})",
+                        tag, slug
+                    );
                 }
             }
         }
         Ok(tags)
     }
 
-    pub async fn try_publish(&self, post: Post) {
-        if let Err(err) = self.publish(post).await {
-            error!("Failed: {}", err);
+    /// User's publication, including page `page` of its posts
+    async fn get_publication(&self, page: u32) -> Result<pub_posts::PubPostsUserPublication> {
+        let body = PubPosts::build_query(pub_posts::Variables {
+            username: self.username.clone(),
+            page: page.into(),
+        });
+        let resp = self.client.post(URL).json(&body).send().await?;
+        let resp: graphql_client::Response<pub_posts::ResponseData> = resp.json().await?;
+        let publication = resp
+            .data
+            .and_then(|data| data.user)
+            .and_then(|user| user.publication)
+            .ok_or_else(|| Error::NotFound {
+                expected: format!("Publication for user: {}", self.username),
+            })?;
+        trace!("Publication ID: {}", publication.id);
+        Ok(publication)
+    }
+
+    /// Page `page` of articles in the user's publication
+    async fn get_articles(&self, page: u32) -> Result<Vec<ListedArticle>> {
+        let publication = self.get_publication(page).await?;
+        // Turn `Option<Vec<Option<PubPostsUserPublicationPosts>>>` into iterator of posts
+        let articles = publication
+            .posts
+            .iter()
+            .flatten()
+            .flatten()
+            .map(|p| ListedArticle {
+                platform: self.name(),
+                id: p.id.clone(),
+                url: p.slug.as_ref().map(|slug| self.get_url(&publication, slug)),
+                title: p.title.clone(),
+                slug: p.slug.clone(),
+                // Not available from the publication's posts
+                canonical_url: None,
+                // Inactive posts have been deleted
+                published: p.is_active,
+            })
+            .collect();
+        Ok(articles)
+    }
+
+    fn get_hostname(&self, publication: &pub_posts::PubPostsUserPublication) -> String {
+        match &publication.domain {
+            Some(domain) if !domain.is_empty() => domain.clone(),
+            _ => format!("{}.hashnode.dev", self.username),
+        }
+    }
+
+    fn get_url(&self, publication: &pub_posts::PubPostsUserPublication, slug: &str) -> String {
+        format!("https://{}/{}", self.get_hostname(publication), slug)
+    }
+
+    /// Current content of an existing article
+    async fn get_post_details(
+        &self,
+        publication: &pub_posts::PubPostsUserPublication,
+        slug: String,
+    ) -> Result<post_details::PostDetailsPost> {
+        let body = PostDetails::build_query(post_details::Variables {
+            slug: slug.clone(),
+            hostname: Some(self.get_hostname(publication)),
+        });
+        let resp = self.client.post(URL).json(&body).send().await?;
+        let resp: graphql_client::Response<post_details::ResponseData> = resp.json().await?;
+        resp.data.and_then(|data| data.post).ok_or_else(|| {
+            Error::NotFound {
+                expected: format!("Hashnode post: {}", slug),
+            }
+            .into()
+        })
+    }
+
+    /// Turn mutation response into the created/updated article
+    fn to_article(
+        &self,
+        publication: &pub_posts::PubPostsUserPublication,
+        success: bool,
+        message: String,
+        post: Option<(String, Option<String>)>,
+    ) -> Result<RemoteArticle> {
+        if !success {
+            return Err(Error::Failed).context(message);
+        }
+        let (id, slug) = post.ok_or_else(|| Error::NotFound {
+            expected: "Post in response".to_owned(),
+        })?;
+        Ok(RemoteArticle {
+            id,
+            url: slug.map(|slug| self.get_url(publication, &slug)),
+        })
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Platform for Hashnode {
+    fn name(&self) -> &'static str {
+        "hashnode"
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            update: true,
+            delete: true,
+            schedule: false,
         }
     }
 
-    async fn publish(&self, post: Post) -> Result<()> {
+    async fn find_existing(&self, post: &Post) -> Result<Option<RemoteArticle>> {
         if self.settings.compare != Compare::Slug {
             warn!(
                 "Hashnode only supports comparing via Slug ({:?}), ignoring: {:?}",
                 post.front_matter.slug, self.settings.compare
             );
         }
-        let (publication_id, post_id) = self.get_pub_and_article_id(&post).await?;
-        info!("Publication ID: {}", publication_id);
-        if let Some(post_id) = post_id {
-            info!(
-                "Matched existing article: id={} ({:?})",
-                post_id, post.front_matter.slug
-            );
-            let is_republished =
-                post.front_matter
-                    .canonical_url
-                    .as_ref()
-                    .map(|url| update_story::isRepublished {
-                        original_article_url: url.to_owned(),
-                    });
-            let is_part_of_publication = update_story::PublicationDetails { publication_id };
-            let tags: Vec<Option<update_story::TagsInput>> = self
-                .get_tag_ids(&post)
-                .await?
-                .iter()
-                .map(|id| {
-                    Some(update_story::TagsInput {
-                        id: id.to_owned(),
-                        name: None,
-                        slug: None,
-                    })
+        let existing = find_in_pages(
+            &self.settings,
+            None,
+            |page| self.get_articles(page),
+            |articles: &[ListedArticle]| {
+                articles.iter().find_map(|article| {
+                    trace!("Article: {:?}", article.slug);
+                    if article.slug == post.front_matter.slug {
+                        Some(article.into())
+                    } else {
+                        None
+                    }
                 })
-                .collect();
-            let input = update_story::UpdateStoryInput {
-                title: post.front_matter.title,
-                slug: post.front_matter.slug,
-                content_markdown: post.body,
-                cover_image_url: None,
-                is_republished,
-                is_part_of_publication,
-                tags,
-                sourced_from_github: None,
-            };
-            let body = UpdateStory::build_query(update_story::Variables { post_id, input });
-            if self.settings.dry {
-            } else {
-                let resp = self
-                    .client
-                    .post(URL)
-                    .header("Authorization", &self.api_token)
-                    .json(&body)
-                    .send()
-                    .await?;
-
-                let resp: graphql_client::Response<update_story::ResponseData> =
-                    resp.json().await?;
-                debug!("{:?}", resp.data);
-            }
+            },
+        )
+        .await?;
+        Ok(existing)
+    }
+
+    async fn create(&self, post: &Post) -> Result<RemoteArticle> {
+        let publication = self.get_publication(0).await?;
+        let publication_id = publication.id.clone();
+        let is_republished =
+            post.front_matter
+                .canonical_url
+                .as_ref()
+                .map(|url| create_pub_story::isRepublished {
+                    original_article_url: url.to_owned(),
+                });
+        let tags = self
+            .get_tag_ids(post)
+            .await?
+            .iter()
+            .map(|id| {
+                Some(create_pub_story::TagsInput {
+                    id: id.to_owned(),
+                    name: None,
+                    slug: None,
+                })
+            })
+            .collect();
+        let input = create_pub_story::CreateStoryInput {
+            content_markdown: post.body.clone(),
+            cover_image_url: post.front_matter.cover_image.clone(),
+            is_anonymous: None,
+            is_republished,
+            slug: post.front_matter.slug.clone(),
+            sourced_from_github: None,
+            tags,
+            title: post.front_matter.title.clone(),
+        };
+        let body = CreatePubStory::build_query(create_pub_story::Variables {
+            input,
+            publication_id,
+            ..Default::default()
+        });
+
+        let resp = self
+            .client
+            .post(URL)
+            .header("Authorization", &self.api_token)
+            .json(&body)
+            .send()
+            .await?;
+
+        let resp: graphql_client::Response<create_pub_story::ResponseData> = resp.json().await?;
+        debug!("{:?}", resp.data);
+        let errors = resp.errors;
+        let story = resp
+            .data
+            .ok_or(Error::Failed)
+            .with_context(|| format!("{:?}", errors))?
+            .create_publication_story;
+        let post = story.post.map(|p| (p.id, p.slug));
+        self.to_article(&publication, story.success, story.message, post)
+    }
+
+    async fn update(&self, existing: &RemoteArticle, post: &Post) -> Result<RemoteArticle> {
+        let publication = self.get_publication(0).await?;
+        let publication_id = publication.id.clone();
+        let settings = &self.settings;
+
+        // Title, content, and tags are required, so use current values for fields not being updated
+        let current = if settings.update_fields.is_empty() {
+            None
         } else {
-            let is_republished = post.front_matter.canonical_url.as_ref().map(|url| {
-                create_pub_story::isRepublished {
+            // Slug is unchanged from the post if not in URL
+            let slug = get_slug(existing)
+                .or_else(|| post.front_matter.slug.clone())
+                .unwrap_or_default();
+            Some(self.get_post_details(&publication, slug).await?)
+        };
+        let current = current.as_ref();
+
+        // `updateStory` replaces the whole post and hashnode doesn't return the original article
+        // URL, so always send the canonical URL or it's removed
+        let is_republished =
+            post.front_matter
+                .canonical_url
+                .as_ref()
+                .map(|url| update_story::isRepublished {
                     original_article_url: url.to_owned(),
-                }
-            });
-            let tags = self
-                .get_tag_ids(&post)
-                .await?
+                });
+        let is_part_of_publication = update_story::PublicationDetails { publication_id };
+        let tag_ids = match current {
+            Some(current) if !settings.is_updated(UpdateField::Tags) => current
+                .tags
                 .iter()
-                .map(|id| {
-                    Some(create_pub_story::TagsInput {
-                        id: id.to_owned(),
-                        name: None,
-                        slug: None,
-                    })
+                .flatten()
+                .flatten()
+                .map(|tag| tag.id.clone())
+                .collect(),
+            _ => self.get_tag_ids(post).await?,
+        };
+        let tags: Vec<Option<update_story::TagsInput>> = tag_ids
+            .iter()
+            .map(|id| {
+                Some(update_story::TagsInput {
+                    id: id.to_owned(),
+                    name: None,
+                    slug: None,
                 })
-                .collect();
-            let input = create_pub_story::CreateStoryInput {
-                content_markdown: post.body,
-                cover_image_url: None,
-                is_anonymous: None,
-                is_republished,
-                slug: post.front_matter.slug,
-                sourced_from_github: None,
-                tags,
-                title: post.front_matter.title,
-            };
-            let body = CreatePubStory::build_query(create_pub_story::Variables {
-                input,
-                publication_id,
-                ..Default::default()
-            });
-
-            if self.settings.dry {
-            } else {
-                let resp = self
-                    .client
-                    .post(URL)
-                    .header("Authorization", &self.api_token)
-                    .json(&body)
-                    .send()
-                    .await?;
-
-                let resp: graphql_client::Response<create_pub_story::ResponseData> =
-                    resp.json().await?;
-                debug!("{:?}", resp.data);
+            })
+            .collect();
+        // Value from post if field is being updated, otherwise the current value
+        let select = |field, value: Option<String>, current_value: Option<&Option<String>>| {
+            match current_value {
+                Some(current_value) if !settings.is_updated(field) => current_value.clone(),
+                _ => value,
             }
-        }
+        };
+        let input = update_story::UpdateStoryInput {
+            title: select(
+                UpdateField::Title,
+                Some(post.front_matter.title.clone()),
+                current.map(|c| &c.title),
+            )
+            .unwrap_or_default(),
+            slug: select(
+                UpdateField::Slug,
+                post.front_matter.slug.clone(),
+                current.map(|c| &c.slug),
+            ),
+            content_markdown: select(
+                UpdateField::Body,
+                Some(post.body.clone()),
+                current.map(|c| &c.content_markdown),
+            )
+            .unwrap_or_default(),
+            cover_image_url: select(
+                UpdateField::CoverImage,
+                post.front_matter.cover_image.clone(),
+                current.map(|c| &c.cover_image),
+            ),
+            is_republished,
+            is_part_of_publication,
+            tags,
+            // Same as when created (also not returned by hashnode)
+            sourced_from_github: None,
+        };
+        let body = UpdateStory::build_query(update_story::Variables {
+            post_id: existing.id.clone(),
+            input,
+        });
+        let resp = self
+            .client
+            .post(URL)
+            .header("Authorization", &self.api_token)
+            .json(&body)
+            .send()
+            .await?;
 
-        Ok(())
+        let resp: graphql_client::Response<update_story::ResponseData> = resp.json().await?;
+        debug!("{:?}", resp.data);
+        let errors = resp.errors;
+        let story = resp
+            .data
+            .ok_or(Error::Failed)
+            .with_context(|| format!("{:?}", errors))?
+            .update_story;
+        let post = story.post.map(|p| (p.id, p.slug));
+        self.to_article(&publication, story.success, story.message, post)
     }
 
-    async fn get_pub_and_article_id(&self, post: &Post) -> Result<(String, Option<String>)> {
-        let body = PubPosts::build_query(pub_posts::Variables {
-            username: self.username.clone(),
-            page: 0,
+    async fn delete(&self, existing: &RemoteArticle) -> Result<()> {
+        let body = DeletePost::build_query(delete_post::Variables {
+            id: existing.id.clone(),
         });
-        let resp = self.client.post(URL).json(&body).send().await?;
-        let resp: graphql_client::Response<pub_posts::ResponseData> = resp.json().await?;
-        let publication = resp
+        let resp = self
+            .client
+            .post(URL)
+            .header("Authorization", &self.api_token)
+            .json(&body)
+            .send()
+            .await?;
+
+        let resp: graphql_client::Response<delete_post::ResponseData> = resp.json().await?;
+        debug!("{:?}", resp.data);
+        let errors = resp.errors;
+        let deleted = resp
             .data
-            .and_then(|data| data.user)
-            .and_then(|user| user.publication)
-            .unwrap();
-        let pub_id = publication.id;
-        let existing_id = publication.posts.unwrap_or_default().iter().find_map(|p| {
-            p.as_ref().and_then(|p| {
-                trace!("Article: {:?}", p.slug);
-                if p.slug == post.front_matter.slug {
-                    Some(p.id.clone())
-                } else {
-                    None
-                }
-            })
-        });
-        Ok((pub_id, existing_id))
+            .ok_or(Error::Failed)
+            .with_context(|| format!("{:?}", errors))?
+            .delete_post;
+        if !deleted.success {
+            return Err(Error::Failed).context(deleted.message);
+        }
+        Ok(())
+    }
+
+    async fn list(&self) -> Result<Vec<ListedArticle>> {
+        collect_pages(&self.settings, None, |page| self.get_articles(page)).await
+    }
+
+    async fn get_content(&self, existing: &RemoteArticle) -> Result<Option<Content>> {
+        let slug = match get_slug(existing) {
+            Some(slug) => slug,
+            None => return Ok(None),
+        };
+        let publication = self.get_publication(0).await?;
+        let current = self.get_post_details(&publication, slug).await?;
+        Ok(Some(Content {
+            title: current.title.unwrap_or_default(),
+            tags: vec![],
+            body: current.content_markdown.unwrap_or_default(),
+        }))
+    }
+
+    fn to_content(&self, post: &Post) -> Content {
+        // Tags aren't compared: hashnode drops tags it has no match for, which
+        // can't be known without looking each one up
+        Content {
+            tags: vec![],
+            ..post.into()
+        }
     }
 }
 
+/// Slug of an existing article is the last part of its URL
+fn get_slug(existing: &RemoteArticle) -> Option<String> {
+    existing
+        .url
+        .as_ref()
+        .and_then(|url| url.rsplit('/').next())
+        .map(String::from)
+}
+
 fn parse_tag_html(text: &str) -> Result<ExtraData> {
     let mut reader = quick_xml::Reader::from_str(&text);
     reader.check_end_names(false).trim_text(true);
@@ -1,7 +1,7 @@
 #![cfg(feature = "medium")]
 
 /// https://github.com/Medium/medium-api-docs
-use crate::{post::Post, *};
+use crate::{platforms::*, post::Post, *};
 
 const URL: &str = "https://api.medium.com/v1";
 
@@ -42,11 +42,12 @@ impl From<Post> for Article {
         } else {
             PublishStatus::Draft
         });
+        let tags = Some(item.front_matter.all_tags()).filter(|tags| !tags.is_empty());
         Self {
             title: item.front_matter.title,
             content_format: ContentFormat::Markdown,
             content: item.body,
-            tags: item.front_matter.tags,
+            tags,
             canonical_url: item.front_matter.canonical_url,
             publish_status,
             license: None,
@@ -56,7 +57,6 @@ impl From<Post> for Article {
 }
 
 pub struct Medium {
-    settings: Settings,
     api_token: String,
     pub_id: Option<String>,
     client: reqwest::Client,
@@ -65,22 +65,30 @@ pub struct Medium {
 impl Medium {
     pub fn new(api_token: String, pub_id: Option<String>, settings: Settings) -> Self {
         info!("Cross-posting to medium");
+        if settings.compare != Compare::CanonicalUrl {
+            warn!(
+                "Medium only supports comparing via canonical URL, ignoring: {:?}",
+                settings.compare
+            );
+        }
         let client = reqwest::Client::new();
         Self {
-            settings,
             api_token,
             pub_id,
             client,
         }
     }
 
-    pub async fn try_publish(&self, post: Post) {
-        if let Err(err) = self.publish(post).await {
-            error!("Failed: {}", err);
-        }
+    pub fn from_opts(opts: &Opts) -> Option<Self> {
+        let api_token = opts.medium_api_token.as_ref()?;
+        Some(Self::new(
+            api_token.clone(),
+            opts.medium_publication_id.clone(),
+            opts.settings.clone(),
+        ))
     }
 
-    async fn publish(&self, post: Post) -> Result<()> {
+    async fn get_user(&self) -> Result<UserData> {
         let resp = self
             .client
             .get(format!("{}/me", URL))
@@ -92,85 +100,120 @@ impl Medium {
             "Authenticated: {} ({} {})",
             user.username, user.name, user.id
         );
-        let has_existing = self.find_existing(&post, &user).await.is_ok();
-        match self.settings.operation {
-            Operation::Auto => {
-                if has_existing {
-                    warn!("Skipping existing article.  Medium doesn't support update.");
-                    return Ok(());
-                }
-            }
-            Operation::Create => {
-                if has_existing {
-                    return Err(Error::Failed).with_context(|| {
-                        format!(
-                            "Existing article matching {:?}: {:?}",
-                            self.settings.compare, post
-                        )
-                    });
-                }
-            }
-            Operation::Update => {
-                if !has_existing {
-                    let expected = format!(
-                        "Existing article matching {:?}: {:?}",
-                        self.settings.compare, post
-                    );
-                    return Err(Error::NotFound { expected }.into());
-                }
-            }
-        }
+        Ok(user)
+    }
 
-        if self.settings.dry {
-            // Do nothing
-        } else {
-            let body: Article = post.into();
-            let resp = self
-                .client
-                .post(format!("{}/users/{}/posts", URL, user.id))
-                .auth(self)
-                .json(&body)
-                .send()
-                .await?;
-            info!("{:?}", resp);
+    /// RSS feed of the user's stories
+    async fn get_feed(&self) -> Result<rss::Channel> {
+        let user = self.get_user().await?;
+        let feed = self
+            .client
+            .get(format!("https://medium.com/feed/@{}", user.username))
+            .send()
+            .await?
+            .bytes()
+            .await?;
+        // Feed only contains the most recent stories and can't be paged
+        Ok(rss::Channel::read_from(&feed[..])?)
+    }
+}
+
+fn to_article(item: rss::Item) -> Option<RemoteArticle> {
+    let link = item.link?;
+    let id = item.guid.map_or_else(|| link.clone(), |guid| guid.value);
+    Some(RemoteArticle {
+        id,
+        url: Some(link),
+    })
+}
+
+#[async_trait::async_trait(?Send)]
+impl Platform for Medium {
+    fn name(&self) -> &'static str {
+        "medium"
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        // Medium API only supports creating articles
+        Capabilities {
+            update: false,
+            delete: false,
+            schedule: false,
         }
-        Ok(())
     }
 
-    async fn find_existing(&self, post: &Post, user: &UserData) -> Result<()> {
-        if self.settings.compare == Compare::CanonicalUrl {
-            if let Some(canonical_url) = &post.front_matter.canonical_url {
-                let feed = self
-                    .client
-                    .get(format!("https://medium.com/feed/@{}", user.username))
-                    .send()
-                    .await?
-                    .bytes()
-                    .await?;
-                let channel = rss::Channel::read_from(&feed[..])?;
-                for item in channel.items {
-                    if let Some(link) = item.link {
-                        let story = self.client.get(link.clone()).send().await?.text().await?;
-                        if let Some(story_canonical_url) = parse_article_canonical(&story) {
-                            debug!(
-                                "Found canonical URL: href={:?} ({})",
-                                story_canonical_url, link
-                            );
-                            if &story_canonical_url == canonical_url {
-                                info!("Matched existing article: {}", canonical_url);
-                                return Ok(());
-                            }
+    async fn find_existing(&self, post: &Post) -> Result<Option<RemoteArticle>> {
+        if let Some(canonical_url) = &post.front_matter.canonical_url {
+            let channel = self.get_feed().await?;
+            for item in channel.items {
+                if let Some(link) = &item.link {
+                    let story = self.client.get(link.clone()).send().await?.text().await?;
+                    if let Some(story_canonical_url) = parse_article_canonical(&story) {
+                        debug!(
+                            "Found canonical URL: href={:?} ({})",
+                            story_canonical_url, link
+                        );
+                        if &story_canonical_url == canonical_url {
+                            info!("Matched existing article: {}", canonical_url);
+                            return Ok(to_article(item));
                         }
                     }
                 }
-            } else {
-                warn!("No canonical URL");
             }
+        } else {
+            warn!("No canonical URL");
         }
-        Err(Error::NotFound {
-            expected: "Existing post".to_owned(),
+        Ok(None)
+    }
+
+    async fn list(&self) -> Result<Vec<ListedArticle>> {
+        let channel = self.get_feed().await?;
+        let mut articles = vec![];
+        for item in channel.items {
+            let title = item.title.clone();
+            let article = match to_article(item) {
+                Some(article) => article,
+                None => continue,
+            };
+            // Canonical URL is only in the story itself
+            let canonical_url = match &article.url {
+                Some(url) => {
+                    let story = self.client.get(url).send().await?.text().await?;
+                    parse_article_canonical(&story)
+                }
+                None => None,
+            };
+            articles.push(ListedArticle {
+                platform: self.name(),
+                id: article.id,
+                url: article.url,
+                title,
+                slug: None,
+                canonical_url,
+                // Feed only contains published stories
+                published: Some(true),
+            });
         }
-        .into())
+        Ok(articles)
+    }
+
+    async fn create(&self, post: &Post) -> Result<RemoteArticle> {
+        let user = self.get_user().await?;
+        let body: Article = post.clone().into();
+        let resp = self
+            .client
+            .post(format!("{}/users/{}/posts", URL, user.id))
+            .auth(self)
+            .json(&body)
+            .send()
+            .await?
+            .error_for_status()?;
+        info!("{:?}", resp);
+        let post = resp.json::<PostResponse>().await?.data;
+        Ok(RemoteArticle {
+            id: post.id,
+            url: Some(post.url),
+        })
     }
 }
 
@@ -239,6 +282,17 @@ struct UserData {
     image_url: String,
 }
 
+#[derive(serde::Deserialize)]
+struct PostResponse {
+    data: PostData,
+}
+
+#[derive(serde::Deserialize)]
+struct PostData {
+    id: String,
+    url: String,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
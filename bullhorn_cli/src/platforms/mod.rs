@@ -3,3 +3,966 @@ pub mod github_pages;
 pub mod hashnode;
 pub mod medium;
 pub mod tumblr;
+
+use crate::{post::Post, *};
+
+/// Article that already exists on a platform
+#[derive(Clone, Debug, Default)]
+pub struct RemoteArticle {
+    /// Platform-specific identifier used to update the article
+    pub id: String,
+    pub url: Option<String>,
+}
+
+/// Article listed on a platform
+#[derive(Clone, Debug, Default, serde::Serialize)]
+pub struct ListedArticle {
+    pub platform: &'static str,
+    pub id: String,
+    pub url: Option<String>,
+    pub title: Option<String>,
+    pub slug: Option<String>,
+    pub canonical_url: Option<String>,
+    /// `None` if the platform doesn't say
+    pub published: Option<bool>,
+}
+
+impl From<&ListedArticle> for RemoteArticle {
+    fn from(item: &ListedArticle) -> Self {
+        Self {
+            id: item.id.clone(),
+            url: item.url.clone(),
+        }
+    }
+}
+
+/// Result of publishing a post to a platform
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Created,
+    Updated,
+    Skipped,
+    Deleted,
+    Failed,
+}
+
+impl std::fmt::Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        let status = match self {
+            Status::Created => "created",
+            Status::Updated => "updated",
+            Status::Skipped => "skipped",
+            Status::Deleted => "deleted",
+            Status::Failed => "failed",
+        };
+        write!(f, "{}", status)
+    }
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct PublishOutcome {
+    pub platform: &'static str,
+    pub status: Status,
+    pub remote_id: Option<String>,
+    pub remote_url: Option<String>,
+    pub error: Option<String>,
+    /// What would have been written, if this was a dry run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preview: Option<Preview>,
+}
+
+/// Changes a dry run would have made
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct Preview {
+    /// `Created`, `Updated`, or `Deleted`
+    pub action: Status,
+    /// Unified diff from the article's current content to what would be written
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diff: Option<String>,
+}
+
+/// Parts of an article shown when previewing changes
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Content {
+    pub title: String,
+    pub tags: Vec<String>,
+    pub body: String,
+}
+
+impl From<&Post> for Content {
+    fn from(item: &Post) -> Self {
+        Self {
+            title: item.front_matter.title.clone(),
+            tags: item.front_matter.all_tags(),
+            body: item.body.clone(),
+        }
+    }
+}
+
+impl Content {
+    fn to_text(&self) -> String {
+        let mut text = format!("title: {}\ntags: {}\n\n", self.title, self.tags.join(", "));
+        text.push_str(&self.body);
+        if !text.ends_with('\n') {
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Names of fields that differ between `self` and `other`
+    pub fn changed(&self, other: &Content) -> Vec<&'static str> {
+        let mut changed = vec![];
+        if self.title != other.title {
+            changed.push("title");
+        }
+        if self.tags != other.tags {
+            changed.push("tags");
+        }
+        // Platforms may add or remove trailing whitespace
+        if self.body.trim_end() != other.body.trim_end() {
+            changed.push("body");
+        }
+        changed
+    }
+
+    /// Unified diff from `self` (current) to `new`
+    pub fn diff(&self, new: &Content) -> String {
+        similar::udiff::unified_diff(
+            similar::Algorithm::Myers,
+            &self.to_text(),
+            &new.to_text(),
+            3,
+            Some(("remote", "local")),
+        )
+    }
+}
+
+impl PublishOutcome {
+    fn new(platform: &dyn Platform, status: Status, article: Option<RemoteArticle>) -> Self {
+        let (remote_id, remote_url) = match article {
+            Some(article) => (Some(article.id), article.url),
+            None => (None, None),
+        };
+        Self {
+            platform: platform.name(),
+            status,
+            remote_id,
+            remote_url,
+            error: None,
+            preview: None,
+        }
+    }
+
+    pub fn failed(platform: &dyn Platform, err: &anyhow::Error) -> Self {
+        Self {
+            error: Some(format!("{:#}", err)),
+            ..Self::new(platform, Status::Failed, None)
+        }
+    }
+}
+
+/// What a platform supports beyond creating articles
+#[derive(Clone, Debug, Default)]
+pub struct Capabilities {
+    pub update: bool,
+    pub delete: bool,
+    /// Posts with a future date can be scheduled, rather than skipped until then
+    pub schedule: bool,
+}
+
+/// Destination articles are cross-posted to
+#[async_trait::async_trait(?Send)]
+pub trait Platform {
+    /// Name used in logs (e.g. `devto`)
+    fn name(&self) -> &'static str;
+
+    fn capabilities(&self) -> Capabilities;
+
+    /// Find article matching `post` (per `Settings::compare`), if one exists
+    async fn find_existing(&self, post: &Post) -> Result<Option<RemoteArticle>>;
+
+    async fn create(&self, post: &Post) -> Result<RemoteArticle>;
+
+    async fn update(&self, _existing: &RemoteArticle, _post: &Post) -> Result<RemoteArticle> {
+        Err(self.unsupported("update"))
+    }
+
+    async fn delete(&self, _existing: &RemoteArticle) -> Result<()> {
+        Err(self.unsupported("delete"))
+    }
+
+    /// All of the user's articles
+    async fn list(&self) -> Result<Vec<ListedArticle>> {
+        Err(self.unsupported("list"))
+    }
+
+    /// Current content of an existing article, `None` if the platform can't retrieve it
+    async fn get_content(&self, _existing: &RemoteArticle) -> Result<Option<Content>> {
+        Ok(None)
+    }
+
+    /// Content written to the platform for `post`
+    fn to_content(&self, post: &Post) -> Content {
+        post.into()
+    }
+
+    fn unsupported(&self, operation: &str) -> anyhow::Error {
+        Error::Unsupported {
+            platform: self.name().to_owned(),
+            operation: operation.to_owned(),
+        }
+        .into()
+    }
+}
+
+/// Apply `normalize` to each tag, dropping duplicates and tags it makes empty, and warning
+/// about tags `platform` wouldn't accept as written
+pub fn normalize_tags(
+    platform: &str,
+    tags: &[String],
+    normalize: impl Fn(&str) -> String,
+) -> Vec<String> {
+    let mut normalized: Vec<String> = vec![];
+    for tag in tags {
+        let new_tag = normalize(tag);
+        if new_tag.is_empty() {
+            warn!("{}: Dropping tag `{}`", platform, tag);
+            continue;
+        }
+        if &new_tag != tag {
+            warn!("{}: Changing tag `{}` to `{}`", platform, tag, new_tag);
+        }
+        if !normalized.contains(&new_tag) {
+            normalized.push(new_tag);
+        }
+    }
+    normalized
+}
+
+/// Create platforms that are enabled and have the required credentials
+pub fn enabled(opts: &Opts) -> Vec<Box<dyn Platform>> {
+    [
+        Platforms::Devto,
+        Platforms::Hashnode,
+        Platforms::Medium,
+        Platforms::Tumblr,
+    ]
+    .iter()
+    .filter(|platform| opts.is_enabled((*platform).clone()))
+    .filter_map(|platform| create(opts, platform))
+    .collect()
+}
+
+/// Create `platform`, `None` if it isn't supported or is missing credentials
+pub fn create(opts: &Opts, platform: &Platforms) -> Option<Box<dyn Platform>> {
+    match platform {
+        #[cfg(feature = "devto")]
+        Platforms::Devto => devto::Devto::from_opts(opts).map(|p| Box::new(p) as Box<dyn Platform>),
+        #[cfg(feature = "hashnode")]
+        Platforms::Hashnode => {
+            hashnode::Hashnode::from_opts(opts).map(|p| Box::new(p) as Box<dyn Platform>)
+        }
+        #[cfg(feature = "medium")]
+        Platforms::Medium => {
+            medium::Medium::from_opts(opts).map(|p| Box::new(p) as Box<dyn Platform>)
+        }
+        #[cfg(feature = "tumblr")]
+        Platforms::Tumblr => {
+            tumblr::Tumblr::from_opts(opts).map(|p| Box::new(p) as Box<dyn Platform>)
+        }
+        _ => None,
+    }
+}
+
+/// Log in to `platform` and save the credentials it returns in the config file (or print them
+/// if a dry run)
+pub async fn auth(opts: &Opts, platform: &Platforms) -> Result<()> {
+    match platform {
+        #[cfg(feature = "tumblr")]
+        Platforms::Tumblr => {
+            let auth = tumblr::Auth::from_opts(opts).ok_or_else(|| Error::NotFound {
+                expected: "Tumblr consumer key and secret".to_owned(),
+            })?;
+            let token = auth.auth().await?;
+            let values = [
+                (TUMBLR_OAUTH_TOKEN, token.token.as_str()),
+                (TUMBLR_OAUTH_TOKEN_SECRET, token.token_secret.as_str()),
+            ];
+            if opts.settings.dry {
+                for (key, value) in &values {
+                    println!("{}: {}", key, value);
+                }
+            } else {
+                let path = config_path(&opts.settings)?;
+                update_config(&path, &values)?;
+                info!("Saved credentials to: {}", path.display());
+            }
+            Ok(())
+        }
+        _ => Err(Error::Unsupported {
+            platform: format!("{:?}", platform),
+            operation: "auth".to_owned(),
+        }
+        .into()),
+    }
+}
+
+/// Fetch pages of results (numbered from 0) until one is empty or has fewer than `per_page`
+/// items.  Stops early once `find` returns `Some`, and fails with `Error::PageLimit` if
+/// `Settings::max_pages` is reached first (the item might be on a later page).
+pub async fn find_in_pages<T, R, F, Fut>(
+    settings: &Settings,
+    per_page: Option<usize>,
+    mut fetch_page: F,
+    mut find: impl FnMut(&[T]) -> Option<R>,
+) -> Result<Option<R>>
+where
+    F: FnMut(u32) -> Fut,
+    Fut: std::future::Future<Output = Result<Vec<T>>>,
+{
+    let mut page = 0;
+    loop {
+        if settings
+            .max_pages
+            .map_or(false, |max_pages| page >= max_pages)
+        {
+            return Err(Error::PageLimit { pages: page }.into());
+        }
+        let items = fetch_page(page).await?;
+        trace!("Page {}: {} items", page, items.len());
+        if let Some(found) = find(&items) {
+            return Ok(Some(found));
+        }
+        if items.is_empty() || per_page.map_or(false, |per_page| items.len() < per_page) {
+            return Ok(None);
+        }
+        page += 1;
+    }
+}
+
+/// Fetch all pages of results, see `find_in_pages()`
+pub async fn collect_pages<T, F, Fut>(
+    settings: &Settings,
+    per_page: Option<usize>,
+    fetch_page: F,
+) -> Result<Vec<T>>
+where
+    T: Clone,
+    F: FnMut(u32) -> Fut,
+    Fut: std::future::Future<Output = Result<Vec<T>>>,
+{
+    let mut all = vec![];
+    let result = find_in_pages(
+        settings,
+        per_page,
+        fetch_page,
+        |items: &[T]| -> Option<()> {
+            all.extend_from_slice(items);
+            None
+        },
+    )
+    .await;
+    match result {
+        // Only the first `max_pages` are wanted
+        Err(err) if matches!(err.downcast_ref(), Some(Error::PageLimit { .. })) => {
+            debug!(
+                "Stopping after {} pages",
+                settings.max_pages.unwrap_or_default()
+            );
+        }
+        result => {
+            result?;
+        }
+    }
+    Ok(all)
+}
+
+/// Delete (or unpublish) the article matching `post`
+async fn delete(
+    platform: &dyn Platform,
+    post: &Post,
+    settings: &Settings,
+    record: Option<&state::Record>,
+) -> Result<PublishOutcome> {
+    if !platform.capabilities().delete {
+        let url = record.and_then(|record| record.url.as_deref());
+        warn!(
+            "{}: Deleting articles not supported, must be deleted manually: {}",
+            platform.name(),
+            url.unwrap_or("unknown URL")
+        );
+        return Ok(PublishOutcome::new(
+            platform,
+            Status::Skipped,
+            record.map(RemoteArticle::from),
+        ));
+    }
+    let existing = match record {
+        Some(record) => Some(record.into()),
+        None => platform.find_existing(post).await?,
+    };
+    let outcome = match existing {
+        None => {
+            info!("{}: Nothing to delete: {:?}", platform.name(), post.path);
+            PublishOutcome::new(platform, Status::Skipped, None)
+        }
+        Some(existing) if settings.dry => PublishOutcome {
+            preview: Some(Preview {
+                action: Status::Deleted,
+                diff: None,
+            }),
+            ..PublishOutcome::new(platform, Status::Skipped, Some(existing))
+        },
+        Some(existing) => {
+            platform.delete(&existing).await?;
+            PublishOutcome::new(platform, Status::Deleted, Some(existing))
+        }
+    };
+    Ok(outcome)
+}
+
+pub async fn try_publish(
+    platform: &dyn Platform,
+    post: &Post,
+    settings: &Settings,
+    record: Option<&state::Record>,
+) -> PublishOutcome {
+    match publish(platform, post, settings, record).await {
+        Ok(outcome) => outcome,
+        Err(err) => {
+            error!("{}: Failed: {:#}", platform.name(), err);
+            PublishOutcome::failed(platform, &err)
+        }
+    }
+}
+
+async fn publish(
+    platform: &dyn Platform,
+    post: &Post,
+    settings: &Settings,
+    record: Option<&state::Record>,
+) -> Result<PublishOutcome> {
+    if settings.operation == Operation::Delete {
+        return delete(platform, post, settings, record).await;
+    }
+
+    if let Some(date) = post.front_matter.scheduled_date(chrono::Utc::now()) {
+        if !platform.capabilities().schedule {
+            info!(
+                "{}: Scheduled for {}, skipping: {:?}",
+                platform.name(),
+                date,
+                post.path
+            );
+            return Ok(PublishOutcome::new(
+                platform,
+                Status::Skipped,
+                record.map(RemoteArticle::from),
+            ));
+        }
+    }
+
+    if let Some(record) = record {
+        let is_unchanged = record.hash.is_some() && record.hash == post.content_hash().ok();
+        if is_unchanged && !settings.force && settings.operation != Operation::Create {
+            info!(
+                "{}: Unchanged since last published, skipping: {:?}",
+                platform.name(),
+                post.path
+            );
+            return Ok(PublishOutcome::new(
+                platform,
+                Status::Skipped,
+                Some(record.into()),
+            ));
+        }
+    }
+
+    if settings.operation == Operation::Update && !platform.capabilities().update {
+        return Err(platform.unsupported("update"));
+    }
+
+    // Prefer article recorded in state over searching the platform
+    let existing = match record {
+        Some(record) => Some(record.into()),
+        None => platform.find_existing(post).await?,
+    };
+    if let Some(ref existing) = existing {
+        info!(
+            "{}: Matched existing article: id={} ({:?})",
+            platform.name(),
+            existing.id,
+            post.path
+        );
+    }
+    // Same checks and messages for every platform
+    let expected = || {
+        format!(
+            "{} article matching {:?} of {:?}",
+            platform.name(),
+            settings.compare,
+            post.path
+        )
+    };
+    match (&settings.operation, &existing) {
+        (Operation::Auto, Some(_)) if !platform.capabilities().update => {
+            warn!(
+                "{}: Skipping existing article, update not supported",
+                platform.name()
+            );
+            return Ok(PublishOutcome::new(
+                platform,
+                Status::Skipped,
+                existing.clone(),
+            ));
+        }
+        (Operation::Create, Some(existing)) => {
+            return Err(Error::Exists {
+                expected: expected(),
+                id: existing.id.clone(),
+            }
+            .into());
+        }
+        (Operation::Update, None) => {
+            return Err(Error::NotFound {
+                expected: expected(),
+            }
+            .into());
+        }
+        _ => {}
+    }
+
+    let outcome = if settings.dry {
+        let preview = preview(platform, existing.as_ref(), post).await;
+        PublishOutcome {
+            preview: Some(preview),
+            ..PublishOutcome::new(platform, Status::Skipped, existing)
+        }
+    } else if let Some(existing) = existing {
+        let article = match platform.update(&existing, post).await {
+            // Recorded article was deleted from the platform, so match the post again
+            Err(err) if record.is_some() && is_not_found(&err) => {
+                warn!(
+                    "{}: Recorded article not found, id={}: {:#}",
+                    platform.name(),
+                    existing.id,
+                    err
+                );
+                match platform.find_existing(post).await? {
+                    Some(existing) => platform.update(&existing, post).await?,
+                    None if settings.operation == Operation::Update => {
+                        return Err(Error::NotFound {
+                            expected: expected(),
+                        }
+                        .into());
+                    }
+                    None => {
+                        let article = platform.create(post).await?;
+                        return Ok(PublishOutcome::new(
+                            platform,
+                            Status::Created,
+                            Some(article),
+                        ));
+                    }
+                }
+            }
+            result => result?,
+        };
+        PublishOutcome::new(platform, Status::Updated, Some(article))
+    } else {
+        let article = platform.create(post).await?;
+        PublishOutcome::new(platform, Status::Created, Some(article))
+    };
+    Ok(outcome)
+}
+
+/// Whether `err` is because an article doesn't exist, e.g. it was deleted
+fn is_not_found(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| {
+        matches!(cause.downcast_ref(), Some(Error::NotFound { .. }))
+            || cause
+                .downcast_ref::<reqwest::Error>()
+                .and_then(reqwest::Error::status)
+                == Some(reqwest::StatusCode::NOT_FOUND)
+    })
+}
+
+/// Describe what publishing `post` would do
+async fn preview(
+    platform: &dyn Platform,
+    existing: Option<&RemoteArticle>,
+    post: &Post,
+) -> Preview {
+    let new = platform.to_content(post);
+    let (action, current) = match existing {
+        Some(existing) => {
+            let current = match platform.get_content(existing).await {
+                Ok(current) => current,
+                Err(err) => {
+                    warn!(
+                        "{}: Unable to get current article: {}",
+                        platform.name(),
+                        err
+                    );
+                    None
+                }
+            };
+            (Status::Updated, current)
+        }
+        // Everything is new
+        None => (Status::Created, Some(Content::default())),
+    };
+    Preview {
+        action,
+        diff: current.map(|current| current.diff(&new)),
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    /// Platform that records calls instead of making requests
+    #[derive(Default)]
+    pub(crate) struct Mock {
+        pub existing: Option<RemoteArticle>,
+        /// Ids of articles that were deleted from the platform
+        pub deleted: Vec<&'static str>,
+        pub calls: RefCell<Vec<&'static str>>,
+    }
+
+    #[async_trait::async_trait(?Send)]
+    impl Platform for Mock {
+        fn name(&self) -> &'static str {
+            "mock"
+        }
+
+        fn capabilities(&self) -> Capabilities {
+            Capabilities {
+                update: true,
+                delete: true,
+                schedule: true,
+            }
+        }
+
+        async fn find_existing(&self, _post: &Post) -> Result<Option<RemoteArticle>> {
+            self.calls.borrow_mut().push("find_existing");
+            Ok(self.existing.clone())
+        }
+
+        async fn create(&self, _post: &Post) -> Result<RemoteArticle> {
+            self.calls.borrow_mut().push("create");
+            Ok(RemoteArticle {
+                id: "new".to_owned(),
+                url: None,
+            })
+        }
+
+        async fn update(&self, existing: &RemoteArticle, _post: &Post) -> Result<RemoteArticle> {
+            self.calls.borrow_mut().push("update");
+            if self.deleted.contains(&existing.id.as_str()) {
+                return Err(Error::NotFound {
+                    expected: format!("mock article {}", existing.id),
+                }
+                .into());
+            }
+            Ok(existing.clone())
+        }
+
+        async fn delete(&self, _existing: &RemoteArticle) -> Result<()> {
+            self.calls.borrow_mut().push("delete");
+            Ok(())
+        }
+    }
+
+    /// Platform that can only create articles
+    struct CreateOnly(Mock);
+
+    #[async_trait::async_trait(?Send)]
+    impl Platform for CreateOnly {
+        fn name(&self) -> &'static str {
+            "create_only"
+        }
+
+        fn capabilities(&self) -> Capabilities {
+            Capabilities::default()
+        }
+
+        async fn find_existing(&self, post: &Post) -> Result<Option<RemoteArticle>> {
+            self.0.find_existing(post).await
+        }
+
+        async fn create(&self, post: &Post) -> Result<RemoteArticle> {
+            self.0.create(post).await
+        }
+    }
+
+    fn existing() -> Option<RemoteArticle> {
+        Some(RemoteArticle {
+            id: "1234".to_owned(),
+            url: None,
+        })
+    }
+
+    fn with_operation(operation: Operation) -> Settings {
+        Settings {
+            operation,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn operation() {
+        let post = Post::new("---\ntitle: title\n---\nbody").unwrap();
+        let missing = Mock::default();
+        let found = Mock {
+            existing: existing(),
+            ..Default::default()
+        };
+
+        let auto = with_operation(Operation::Auto);
+        let outcome = try_publish(&missing, &post, &auto, None).await;
+        assert_eq!(outcome.status, Status::Created);
+        let outcome = try_publish(&found, &post, &auto, None).await;
+        assert_eq!(outcome.status, Status::Updated);
+
+        let create = with_operation(Operation::Create);
+        let outcome = try_publish(&found, &post, &create, None).await;
+        assert_eq!(outcome.status, Status::Failed);
+        assert!(outcome.error.unwrap().starts_with("Already exists"));
+
+        let update = with_operation(Operation::Update);
+        let outcome = try_publish(&missing, &post, &update, None).await;
+        assert_eq!(outcome.status, Status::Failed);
+        assert!(outcome.error.unwrap().starts_with("Not found"));
+    }
+
+    #[tokio::test]
+    async fn operation_unsupported() {
+        let post = Post::new("---\ntitle: title\n---\nbody").unwrap();
+        let platform = CreateOnly(Mock {
+            existing: existing(),
+            ..Default::default()
+        });
+
+        let outcome = try_publish(&platform, &post, &with_operation(Operation::Auto), None).await;
+        assert_eq!(outcome.status, Status::Skipped);
+
+        // Fails without searching for the article
+        let outcome = try_publish(&platform, &post, &with_operation(Operation::Update), None).await;
+        assert_eq!(outcome.status, Status::Failed);
+        assert!(outcome.error.unwrap().starts_with("Unsupported"));
+        assert_eq!(*platform.0.calls.borrow(), vec!["find_existing"]);
+    }
+
+    fn record(post: &Post) -> state::Record {
+        state::Record {
+            id: "1234".to_owned(),
+            url: None,
+            hash: post.content_hash().ok(),
+        }
+    }
+
+    /// Pages `[0, 1, 2]`, `[3, 4, 5]`, ... up to `count` items
+    async fn numbers(page: u32, count: u32) -> Result<Vec<u32>> {
+        Ok((page * 3..(page * 3 + 3).min(count)).collect())
+    }
+
+    #[tokio::test]
+    async fn pages() -> Result<()> {
+        let settings = Settings::default();
+        let all = collect_pages(&settings, Some(3), |page| numbers(page, 7)).await?;
+        assert_eq!(all, (0..7).collect::<Vec<_>>());
+        // Last page is full, stop at following empty page
+        let all = collect_pages(&settings, Some(3), |page| numbers(page, 6)).await?;
+        assert_eq!(all.len(), 6);
+
+        let found = find_in_pages(
+            &settings,
+            None,
+            |page| numbers(page, 7),
+            |items: &[u32]| items.iter().find(|i| **i == 4).copied(),
+        )
+        .await?;
+        assert_eq!(found, Some(4));
+
+        let settings = Settings {
+            max_pages: Some(1),
+            ..Default::default()
+        };
+        let all = collect_pages(&settings, Some(3), |page| numbers(page, 7)).await?;
+        assert_eq!(all.len(), 3);
+        // Article might be on a page that wasn't searched
+        let err = find_in_pages(
+            &settings,
+            Some(3),
+            |page| numbers(page, 7),
+            |items: &[u32]| items.iter().find(|i| **i == 6).copied(),
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref(),
+            Some(Error::PageLimit { pages: 1 })
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn unchanged() {
+        let platform = Mock::default();
+        let post = Post::new("---\ntitle: title\n---\nbody").unwrap();
+        let settings = Settings::default();
+        let outcome = try_publish(&platform, &post, &settings, Some(&record(&post))).await;
+        assert_eq!(outcome.status, Status::Skipped);
+        assert!(platform.calls.borrow().is_empty());
+
+        // Changed since recorded
+        let mut changed = post.clone();
+        changed.body = "changed".to_owned();
+        let outcome = try_publish(&platform, &changed, &settings, Some(&record(&post))).await;
+        assert_eq!(outcome.status, Status::Updated);
+        assert_eq!(outcome.remote_id, Some("1234".to_owned()));
+        assert_eq!(*platform.calls.borrow(), vec!["update"]);
+    }
+
+    #[tokio::test]
+    async fn delete() {
+        let post = Post::new("---\ntitle: title\n---\nbody").unwrap();
+        let settings = with_operation(Operation::Delete);
+        let missing = Mock::default();
+        let outcome = try_publish(&missing, &post, &settings, None).await;
+        assert_eq!(outcome.status, Status::Skipped);
+
+        let found = Mock {
+            existing: existing(),
+            ..Default::default()
+        };
+        let outcome = try_publish(&found, &post, &settings, None).await;
+        assert_eq!(outcome.status, Status::Deleted);
+        assert_eq!(outcome.remote_id, Some("1234".to_owned()));
+        assert_eq!(*found.calls.borrow(), vec!["find_existing", "delete"]);
+
+        // Platforms that can't delete are skipped without searching for the article
+        let record = state::Record {
+            id: "1234".to_owned(),
+            ..Default::default()
+        };
+        let create_only = CreateOnly(Mock::default());
+        let outcome = try_publish(&create_only, &post, &settings, Some(&record)).await;
+        assert_eq!(outcome.status, Status::Skipped);
+        assert!(create_only.0.calls.borrow().is_empty());
+
+        let dry = Settings {
+            dry: true,
+            ..with_operation(Operation::Delete)
+        };
+        let outcome = try_publish(&found, &post, &dry, Some(&record)).await;
+        assert_eq!(outcome.status, Status::Skipped);
+        assert_eq!(outcome.preview.unwrap().action, Status::Deleted);
+    }
+
+    #[tokio::test]
+    async fn dry() {
+        let post = Post::new("---\ntitle: title\n---\nbody").unwrap();
+        let platform = Mock::default();
+        let settings = Settings {
+            dry: true,
+            ..Default::default()
+        };
+        let outcome = try_publish(&platform, &post, &settings, None).await;
+        assert_eq!(outcome.status, Status::Skipped);
+        let preview = outcome.preview.unwrap();
+        assert_eq!(preview.action, Status::Created);
+        assert!(preview.diff.unwrap().contains("+title: title"));
+        assert_eq!(*platform.calls.borrow(), vec!["find_existing"]);
+    }
+
+    #[test]
+    fn diff() {
+        let current = Content {
+            title: "title".to_owned(),
+            tags: vec!["tag0".to_owned()],
+            body: "line0\nline1\n".to_owned(),
+        };
+        let new = Content {
+            tags: vec!["tag0".to_owned(), "tag1".to_owned()],
+            ..current.clone()
+        };
+        let diff = current.diff(&new);
+        assert!(diff.starts_with("--- remote\n+++ local\n"));
+        assert!(diff.contains("-tags: tag0\n+tags: tag0, tag1\n"));
+        assert!(!diff.contains("-line0"));
+        assert_eq!(current.diff(&current), "");
+
+        assert_eq!(current.changed(&new), vec!["tags"]);
+        let new = Content {
+            body: "line0\nline1".to_owned(),
+            ..current.clone()
+        };
+        assert!(current.changed(&new).is_empty());
+    }
+
+    #[tokio::test]
+    async fn force() {
+        let platform = Mock::default();
+        let post = Post::new("---\ntitle: title\n---\nbody").unwrap();
+        let settings = Settings {
+            force: true,
+            ..Default::default()
+        };
+        let outcome = try_publish(&platform, &post, &settings, Some(&record(&post))).await;
+        assert_eq!(outcome.status, Status::Updated);
+        assert_eq!(*platform.calls.borrow(), vec!["update"]);
+    }
+
+    #[tokio::test]
+    async fn deleted() {
+        let post = Post::new("---\ntitle: title\n---\nbody changed").unwrap();
+        let platform = Mock {
+            deleted: vec!["1234"],
+            ..Default::default()
+        };
+        let stale = state::Record {
+            hash: None,
+            ..record(&post)
+        };
+        let outcome = try_publish(&platform, &post, &Settings::default(), Some(&stale)).await;
+        assert_eq!(outcome.status, Status::Created);
+        assert_eq!(outcome.remote_id, Some("new".to_owned()));
+        assert_eq!(
+            *platform.calls.borrow(),
+            vec!["update", "find_existing", "create"]
+        );
+
+        // Unless it can be found again
+        let platform = Mock {
+            existing: Some(RemoteArticle {
+                id: "5678".to_owned(),
+                url: None,
+            }),
+            deleted: vec!["1234"],
+            ..Default::default()
+        };
+        let outcome = try_publish(&platform, &post, &Settings::default(), Some(&stale)).await;
+        assert_eq!(outcome.status, Status::Updated);
+        assert_eq!(outcome.remote_id, Some("5678".to_owned()));
+    }
+
+    #[tokio::test]
+    async fn scheduled() {
+        let post = Post::new("---\ntitle: title\ndate: 2999-01-01\n---\nbody").unwrap();
+        let settings = Settings::default();
+        let create_only = CreateOnly(Mock::default());
+        let outcome = try_publish(&create_only, &post, &settings, None).await;
+        assert_eq!(outcome.status, Status::Skipped);
+        assert!(create_only.0.calls.borrow().is_empty());
+
+        // Platforms that can schedule posts create them now
+        let platform = Mock::default();
+        let outcome = try_publish(&platform, &post, &settings, None).await;
+        assert_eq!(outcome.status, Status::Created);
+    }
+}
@@ -1,10 +1,12 @@
 #![cfg(feature = "tumblr")]
 
 /// https://www.tumblr.com/docs/en/api/v2
-use crate::*;
+use crate::{platforms::*, *};
 
 const WWW: &str = "https://www.tumblr.com";
 const URL: &str = "https://api.tumblr.com/v2";
+/// Posts per page when listing posts (maximum 20)
+const PER_PAGE: usize = 20;
 
 pub struct Tumblr {
     settings: Settings,
@@ -26,6 +28,12 @@ impl Tumblr {
         settings: Settings,
     ) -> Self {
         info!("Cross-posting to tumblr");
+        if settings.compare != Compare::CanonicalUrl {
+            warn!(
+                "Tumblr only supports comparing via canonical URL, ignoring: {:?}",
+                settings.compare
+            );
+        }
         let client = reqwest::Client::new();
         Self {
             settings,
@@ -38,47 +46,40 @@ impl Tumblr {
         }
     }
 
-    pub async fn try_publish(&self, post: post::Post) {
-        if let Err(err) = self.publish(post).await {
-            error!("Failed: {}", err);
-        }
+    pub fn from_opts(opts: &Opts) -> Option<Self> {
+        Some(Self::new(
+            opts.tumblr_consumer_key.clone()?,
+            opts.tumblr_consumer_secret.clone()?,
+            opts.tumblr_token.clone()?,
+            opts.tumblr_token_secret.clone()?,
+            opts.tumblr_blog_id.clone()?,
+            opts.settings.clone(),
+        ))
     }
 
-    async fn publish(&self, post: post::Post) -> Result<()> {
-        let resp = self
-            .client
-            .get(format!("{}/blog/{}/posts", URL, self.blog_id))
-            // Only requires api_key authentication, get response in "Neue Post Format"
-            .query(&[("api_key", &self.consumer_key), ("npf", &"true".to_owned())])
-            .send()
-            .await?;
-        let text = resp.text().await?;
-        trace!("Posts {}", text);
-        let posts: Posts = serde_json::from_str(&text)?;
-        let existing = Self::find_existing(&post, &posts);
-        if let Some(ref id) = existing {
-            info!("Matched existing article: id={}", id);
-        }
+    async fn post_link(
+        &self,
+        existing: Option<&RemoteArticle>,
+        post: &post::Post,
+    ) -> Result<RemoteArticle> {
         // Only legacy API supports markdown, Neue Post Format (NPF) doesn't
         // https://github.com/tumblr/docs/blob/master/api.md#post--create-a-new-blog-post-legacy
 
-        // Must authenticate using both client/consumer and user tokens/secrets
-        let token = oauth1_request::Token::from_parts(
-            &self.consumer_key,
-            &self.consumer_secret,
-            &self.token,
-            &self.token_secret,
-        );
-        let tags = post.front_matter.tags.map(|tags| RequestTags { tags });
+        let url = post
+            .front_matter
+            .canonical_url
+            .clone()
+            .ok_or_else(|| Error::NotFound {
+                expected: format!("Canonical URL to link to: {:?}", post.path),
+            })?;
         let request = LinkRequest {
             // If we found existing article this will be Some and we'll update.  Otherwise this is None and we create.
-            id: existing.clone(),
-            title: Some(post.front_matter.title),
-            date: post.front_matter.date,
-            url: post.front_matter.canonical_url.unwrap(),
-            tags,
-            description: post.front_matter.description,
-            ..Default::default()
+            id: existing.map(|existing| existing.id.clone()),
+            ..LinkRequest::new(post, url)
+        };
+        let request = match existing {
+            Some(_) => request.for_update(&self.settings),
+            None => request,
         };
 
         // To create an article: POST {blog_id}/post
@@ -89,36 +90,78 @@ impl Tumblr {
             self.blog_id,
             if existing.is_some() { "/edit" } else { "" }
         );
+        let text = self.post_signed(uri, &request).await?;
+        let resp: PostResponse = serde_json::from_str(&text)?;
+        let PostId { id, id_string } = resp.response;
+        let id = id_string.unwrap_or_else(|| id.to_string());
+        let url = format!("{}/blog/view/{}/{}", WWW, self.blog_id, id);
+        Ok(RemoteArticle { id, url: Some(url) })
+    }
+
+    /// POST `request` signed with the user's OAuth token, returning the response body
+    async fn post_signed<R: oauth1_request::Request>(
+        &self,
+        uri: String,
+        request: &R,
+    ) -> Result<String> {
+        // Must authenticate using both client/consumer and user tokens/secrets
+        let token = oauth1_request::Token::from_parts(
+            &self.consumer_key,
+            &self.consumer_secret,
+            &self.token,
+            &self.token_secret,
+        );
         // Sign the request and create `Authorization` HTTP header
         let auth_header =
-            oauth1_request::post(uri.clone(), &request, &token, oauth1_request::HmacSha1);
+            oauth1_request::post(uri.clone(), request, &token, oauth1_request::HmacSha1);
         // For POST, request body contains `application/x-www-form-urlencoded`
-        let body = oauth1_request::to_form_urlencoded(&request);
+        let body = oauth1_request::to_form_urlencoded(request);
         trace!("{}", auth_header);
         trace!("{}", body);
-        if self.settings.dry {
-        } else {
-            let resp = self
-                .client
-                .post(uri)
-                .header(reqwest::header::AUTHORIZATION, auth_header)
-                .header(
-                    reqwest::header::CONTENT_TYPE,
-                    "application/x-www-form-urlencoded",
-                )
-                .body(body)
-                .send()
-                .await?;
-            debug!("{:?}", &resp);
-            let text = resp.text().await?;
-            debug!("{}", text);
-        }
+        let resp = self
+            .client
+            .post(uri)
+            .header(reqwest::header::AUTHORIZATION, auth_header)
+            .header(
+                reqwest::header::CONTENT_TYPE,
+                "application/x-www-form-urlencoded",
+            )
+            .body(body)
+            .send()
+            .await?
+            .error_for_status()?;
+        debug!("{:?}", &resp);
+        let text = resp.text().await?;
+        debug!("{}", text);
+        Ok(text)
+    }
 
-        Ok(())
+    /// Page of the blog's posts
+    async fn get_posts(&self, page: u32) -> Result<Vec<Post>> {
+        let offset = (page as usize * PER_PAGE).to_string();
+        let limit = PER_PAGE.to_string();
+        self.query_posts(&[("offset", offset.as_str()), ("limit", limit.as_str())])
+            .await
+    }
+
+    async fn query_posts(&self, query: &[(&str, &str)]) -> Result<Vec<Post>> {
+        let resp = self
+            .client
+            .get(format!("{}/blog/{}/posts", URL, self.blog_id))
+            // Only requires api_key authentication, get response in "Neue Post Format"
+            .query(&[("api_key", self.consumer_key.as_str()), ("npf", "true")])
+            .query(query)
+            .send()
+            .await?
+            .error_for_status()?;
+        let text = resp.text().await?;
+        trace!("Posts {}", text);
+        let posts: Posts = serde_json::from_str(&text)?;
+        Ok(posts.response.posts)
     }
 
-    fn find_existing(post: &post::Post, posts: &Posts) -> Option<String> {
-        posts.response.posts.iter().find_map(|p| {
+    fn match_existing(post: &post::Post, posts: &[Post]) -> Option<RemoteArticle> {
+        posts.iter().find_map(|p| {
             p.content
                 .iter()
                 // Find block that is a "link" and contains canonical URL, and return its ID
@@ -129,11 +172,97 @@ impl Tumblr {
                     } => display_url == post.front_matter.canonical_url.as_ref().unwrap(),
                     _ => false,
                 })
-                .map(|_| p.id_string.clone())
+                .map(|_| RemoteArticle {
+                    id: p.id_string.clone(),
+                    url: p.post_url.clone(),
+                })
         })
     }
 }
 
+#[async_trait::async_trait(?Send)]
+impl Platform for Tumblr {
+    fn name(&self) -> &'static str {
+        "tumblr"
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            update: true,
+            delete: true,
+            schedule: true,
+        }
+    }
+
+    async fn find_existing(&self, post: &post::Post) -> Result<Option<RemoteArticle>> {
+        if post.front_matter.canonical_url.is_none() {
+            warn!("No canonical URL");
+            return Ok(None);
+        }
+        find_in_pages(
+            &self.settings,
+            Some(PER_PAGE),
+            |page| self.get_posts(page),
+            |posts: &[Post]| Self::match_existing(post, posts),
+        )
+        .await
+    }
+
+    async fn create(&self, post: &post::Post) -> Result<RemoteArticle> {
+        self.post_link(None, post).await
+    }
+
+    async fn update(&self, existing: &RemoteArticle, post: &post::Post) -> Result<RemoteArticle> {
+        self.post_link(Some(existing), post).await
+    }
+
+    async fn delete(&self, existing: &RemoteArticle) -> Result<()> {
+        // https://github.com/tumblr/docs/blob/master/api.md#postdelete--delete-a-post
+        let uri = format!("{}/blog/{}/post/delete", URL, self.blog_id);
+        let request = DeleteRequest {
+            id: existing.id.clone(),
+        };
+        self.post_signed(uri, &request).await?;
+        Ok(())
+    }
+
+    async fn list(&self) -> Result<Vec<ListedArticle>> {
+        let posts =
+            collect_pages(&self.settings, Some(PER_PAGE), |page| self.get_posts(page)).await?;
+        Ok(posts.into_iter().map(ListedArticle::from).collect())
+    }
+
+    async fn get_content(&self, existing: &RemoteArticle) -> Result<Option<Content>> {
+        let posts = self.query_posts(&[("id", existing.id.as_str())]).await?;
+        Ok(posts.into_iter().next().map(|post| {
+            // Link posts only contain the title and description
+            let (title, description) = post
+                .content
+                .into_iter()
+                .find_map(|block| match block {
+                    ContentBlock::Link {
+                        title, description, ..
+                    } => Some((title, description)),
+                    _ => None,
+                })
+                .unwrap_or_default();
+            Content {
+                title: title.unwrap_or_default(),
+                tags: post.tags,
+                body: description.unwrap_or_default(),
+            }
+        }))
+    }
+
+    fn to_content(&self, post: &post::Post) -> Content {
+        Content {
+            tags: normalize_tags("tumblr", &post.front_matter.all_tags(), normalize_tag),
+            body: post.front_matter.description.clone().unwrap_or_default(),
+            ..post.into()
+        }
+    }
+}
+
 // HTTP request to create/update "link" type post
 #[derive(oauth1_request::Request)]
 struct LinkRequest {
@@ -142,15 +271,91 @@ struct LinkRequest {
     #[oauth1(rename = "type")]
     r#type: String,
     state: Option<String>,
+    /// When a `queue` post is published
+    publish_on: Option<String>,
     tags: Option<RequestTags>,
     date: Option<String>,
     format: Option<String>,
+    slug: Option<String>,
 
     title: Option<String>,
     url: String,
     description: Option<String>,
 }
 
+// HTTP request to delete a post
+#[derive(oauth1_request::Request)]
+struct DeleteRequest {
+    id: String,
+}
+
+impl LinkRequest {
+    fn new(post: &post::Post, url: String) -> Self {
+        // Future posts are added to the queue and published on their date
+        let scheduled = post.front_matter.scheduled_date(chrono::Utc::now());
+        let state = match scheduled {
+            _ if !post.front_matter.is_published() => "draft",
+            Some(_) => "queue",
+            None => "published",
+        };
+        Self {
+            state: Some(state.to_owned()),
+            publish_on: scheduled.as_ref().map(dates::to_iso8601),
+            tags: Some(normalize_tags(
+                "tumblr",
+                &post.front_matter.all_tags(),
+                normalize_tag,
+            ))
+            .filter(|tags| !tags.is_empty())
+            .map(|tags| RequestTags { tags }),
+            date: match scheduled {
+                Some(_) => None,
+                None => post.front_matter.parsed_date().as_ref().map(dates::to_gmt),
+            },
+            slug: post.front_matter.slug.clone(),
+            title: Some(post.front_matter.title.clone()),
+            url,
+            description: post.front_matter.description.clone(),
+            ..Default::default()
+        }
+    }
+
+    /// Only send fields selected by `Settings::update_fields`, Tumblr leaves the rest unchanged
+    fn for_update(self, settings: &Settings) -> Self {
+        let select = |field, value| {
+            if settings.is_updated(field) {
+                value
+            } else {
+                None
+            }
+        };
+        let is_published = settings.is_updated(UpdateField::Published);
+        Self {
+            state: if is_published { self.state } else { None },
+            publish_on: if is_published { self.publish_on } else { None },
+            date: if is_published { self.date } else { None },
+            tags: if settings.is_updated(UpdateField::Tags) {
+                self.tags
+            } else {
+                None
+            },
+            slug: select(UpdateField::Slug, self.slug),
+            title: select(UpdateField::Title, self.title),
+            description: select(UpdateField::Description, self.description),
+            ..self
+        }
+    }
+}
+
+/// Tumblr tags may contain spaces, but not commas (which separate tags) or a leading `#`
+fn normalize_tag(tag: &str) -> String {
+    tag.replace(',', " ")
+        .trim()
+        .trim_start_matches('#')
+        .trim()
+        .to_owned()
+}
+
 // Helper to serialize Vec<_>
 struct RequestTags {
     tags: Vec<String>,
@@ -170,9 +375,11 @@ impl Default for LinkRequest {
             id: None,
             r#type: "link".to_owned(),
             state: None,
+            publish_on: None,
             tags: None,
             date: None,
             format: None,
+            slug: None,
 
             title: None,
             url: String::new(),
@@ -203,25 +410,70 @@ struct PostsResponse {
 #[derive(Debug, serde::Deserialize)]
 struct Blog {}
 
+/// Response to creating/editing a post
+#[derive(Debug, serde::Deserialize)]
+struct PostResponse {
+    response: PostId,
+}
+
 #[derive(Debug, serde::Deserialize)]
+struct PostId {
+    id: u64,
+    id_string: Option<String>,
+}
+
+#[derive(Clone, Debug, serde::Deserialize)]
 struct Post {
     id: u64,
     id_string: String,
+    post_url: Option<String>,
     slug: String,
     summary: String,
+    /// E.g. `published`, `queued`, `draft`, or `private`
+    state: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
     content: Vec<ContentBlock>,
 }
 
+impl From<Post> for ListedArticle {
+    fn from(item: Post) -> Self {
+        // Articles are cross-posted as a link to the canonical URL
+        let (title, canonical_url) = item
+            .content
+            .into_iter()
+            .find_map(|block| match block {
+                ContentBlock::Link {
+                    title, display_url, ..
+                } => Some((title, display_url)),
+                _ => None,
+            })
+            .unwrap_or_default();
+        Self {
+            platform: "tumblr",
+            id: item.id_string,
+            url: item.post_url,
+            title: title
+                .or(Some(item.summary))
+                .filter(|title| !title.is_empty()),
+            slug: Some(item.slug).filter(|slug| !slug.is_empty()),
+            canonical_url,
+            published: item.state.map(|state| state == "published"),
+        }
+    }
+}
+
 /// Serde will serialize these from JSON like, e.g.:
 /// {type="link", display_url="xxx", ...}
 /// https://www.tumblr.com/docs/npf#content-blocks
-#[derive(Debug, serde::Deserialize)]
+#[derive(Clone, Debug, serde::Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 enum ContentBlock {
     Link {
         url: String,
         display_url: Option<String>,
         title: Option<String>,
+        description: Option<String>,
     },
     Text {
         text: String,
@@ -229,8 +481,19 @@ enum ContentBlock {
     Image {},
     Audio {},
     Video {},
+    // Ignore other blocks (e.g. from older posts) rather than fail parsing the whole page
+    #[serde(other)]
+    Other,
 }
 
+/// User credentials obtained by logging in
+#[derive(Debug)]
+pub struct AccessToken {
+    pub token: String,
+    pub token_secret: String,
+}
+
+/// OAuth 1.0a login to obtain user credentials
 pub struct Auth {
     settings: Settings,
     consumer_key: String,
@@ -249,21 +512,28 @@ impl Auth {
         }
     }
 
-    pub async fn try_auth(&self) {
-        if let Err(err) = self.auth().await {
-            error!("Failed: {}", err);
-        }
+    pub fn from_opts(opts: &Opts) -> Option<Self> {
+        Some(Self::new(
+            opts.tumblr_consumer_key.clone()?,
+            opts.tumblr_consumer_secret.clone()?,
+            opts.settings.clone(),
+        ))
     }
 
-    async fn auth(&self) -> Result<()> {
+    pub async fn auth(&self) -> Result<AccessToken> {
+        // Receive the user's approval on a local "callback" server
+        let listener = tokio::net::TcpListener::bind((std::net::Ipv4Addr::LOCALHOST, 0)).await?;
+        let callback = format!("http://{}/callback", listener.local_addr()?);
+        trace!("Callback URL: {}", callback);
+
         let uri = format!("{}/oauth/request_token", WWW);
         let client_credentials =
             oauth1_request::Credentials::new(&self.consumer_key, &self.consumer_secret);
         // Sign request using only client/consumer credentials
         let auth_header =
             oauth1_request::Builder::<_, _>::new(client_credentials, oauth1_request::HmacSha1)
-                // Can optionally specify callback URL here, but Tumblr will use the application default
-                //.callback("https://callback_url")
+                // Overrides the application's default callback URL
+                .callback(callback.as_str())
                 .post(uri.clone(), &());
         trace!("Authorization: {}", auth_header);
         let resp = self
@@ -271,54 +541,30 @@ impl Auth {
             .post(uri)
             .header(reqwest::header::AUTHORIZATION, auth_header)
             .send()
-            .await?;
+            .await?
+            .error_for_status()?;
         debug!("{:?}", resp);
         let resp_body = resp.text().await?;
         // Parse `key0=value0&key1=value1&...` in response body for temporary credentials
-        let mut resp_body_pairs = resp_body
-            .split('&')
-            .map(|pair| pair.split_once('='))
-            .flatten();
-        let temp_token = get_value(&mut resp_body_pairs, "oauth_token")?.to_owned();
-        let temp_token_secret = get_value(&mut resp_body_pairs, "oauth_token_secret")?.to_owned();
+        let pairs = parse_pairs(&resp_body);
+        let temp_token = get_value(&mut pairs.iter().copied(), "oauth_token")?.to_owned();
+        let temp_token_secret =
+            get_value(&mut pairs.iter().copied(), "oauth_token_secret")?.to_owned();
         trace!("Temporary oauth_token: {}", temp_token);
 
-        // Create temporary SQS queue `bullhorn-{temporary token}` to receive oauth_verifier from lambda
-        let queue_name = format!("bullhorn-{}", temp_token);
-        trace!("Creating SQS queue: {}", queue_name);
-        let client = aws_sqs::Client::from_env();
-        let output = client.create_queue().queue_name(queue_name).send().await?;
-        let queue_url = output.queue_url.unwrap();
-        trace!("Created SQS queue: {}", queue_url);
-
         // Show "resource owner" approval website in system default web browser
         let query = format!("{}/oauth/authorize?oauth_token={}", WWW, temp_token);
-        let exit_status = open::that(query)?;
-        // use anyhow::Context;
-        // if exit_status.success() {
-        //     Ok(())
-        // } else if let Some(exit_code) = exit_status.code() {
-        //     Err(Error::Failed).with_context(|| format!("Exit code: {}", exit_code))
-        // } else {
-        //     Err(Error::Failed).context("Unknown exit code")
-        // }
-
-        // Receive oauth_verifier from lambda via SQS
-        let messages = loop {
-            let output = client
-                .receive_message()
-                .queue_url(&queue_url)
-                .send()
-                .await?;
-            if let Some(msgs) = output.messages {
-                if !msgs.is_empty() {
-                    break msgs;
-                }
-            }
+        eprintln!("Authorize bullhorn in your browser: {}", query);
+        eprintln!("If it doesn't complete automatically, paste the URL you were redirected to:");
+        if let Err(err) = open::that(&query) {
+            warn!("Unable to open browser: {}", err);
+        }
+
+        // Whichever arrives first: the browser redirect, or the user pasting the verifier
+        let verifier = tokio::select! {
+            verifier = receive_verifier(listener) => verifier?,
+            verifier = read_verifier() => verifier?,
         };
-        // Delete the temporary SQS queue
-        let _ = client.delete_queue().queue_url(queue_url).send().await?;
-        let verifier = messages[0].body.as_ref().unwrap();
         trace!("Verifier: {}", verifier);
 
         // Exchange client/consumer and temporary credentials for user credentials
@@ -338,14 +584,103 @@ impl Auth {
             .header(reqwest::header::AUTHORIZATION, auth_header)
             .send()
             .await?
+            .error_for_status()?
             .text()
             .await?;
         debug!("Results: {}", resp);
+        let pairs = parse_pairs(&resp);
+        Ok(AccessToken {
+            token: get_value(&mut pairs.iter().copied(), "oauth_token")?.to_owned(),
+            token_secret: get_value(&mut pairs.iter().copied(), "oauth_token_secret")?.to_owned(),
+        })
+    }
+}
 
-        Ok(())
+/// Wait for the browser to be redirected to the callback URL, and get `oauth_verifier` from it
+async fn receive_verifier(listener: tokio::net::TcpListener) -> Result<String> {
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt};
+
+    loop {
+        let (mut stream, addr) = listener.accept().await?;
+        trace!("Callback connection: {}", addr);
+        // Only need the request line, e.g. `GET /callback?oauth_token=..&oauth_verifier=.. HTTP/1.1`
+        let mut request_line = String::new();
+        tokio::io::BufReader::new(&mut stream)
+            .read_line(&mut request_line)
+            .await?;
+        let verifier = parse_verifier(&request_line);
+        let (status, message) = if verifier.is_some() {
+            ("200 OK", "Bullhorn authorized, you can close this window.")
+        } else {
+            // E.g. browser requesting `/favicon.ico`
+            ("404 Not Found", "Not found")
+        };
+        let response = format!(
+            "HTTP/1.1 {}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            message.len(),
+            message
+        );
+        stream.write_all(response.as_bytes()).await?;
+        if let Some(verifier) = verifier {
+            return Ok(verifier);
+        }
+    }
+}
+
+/// Read redirect URL (or just the `oauth_verifier`) pasted by the user
+async fn read_verifier() -> Result<String> {
+    // Tokio's stdin blocks runtime shutdown while waiting for input, so read it on its own thread
+    let (sender, receiver) = tokio::sync::oneshot::channel();
+    std::thread::spawn(move || {
+        let stdin = std::io::stdin();
+        let mut line = String::new();
+        let verifier = loop {
+            line.clear();
+            match stdin.read_line(&mut line) {
+                Ok(0) | Err(_) => break None,
+                Ok(_) => {
+                    if let Some(verifier) = parse_verifier(&line) {
+                        break Some(verifier);
+                    }
+                }
+            }
+        };
+        let _ = sender.send(verifier);
+    });
+    match receiver.await {
+        Ok(Some(verifier)) => Ok(verifier),
+        // Stdin closed, only the callback can complete login
+        _ => futures::future::pending().await,
+    }
+}
+
+/// `oauth_verifier` query parameter in `text`, or all of `text` if it's only the verifier
+fn parse_verifier(text: &str) -> Option<String> {
+    const KEY: &str = "oauth_verifier=";
+    let text = text.trim();
+    let verifier = match text.find(KEY) {
+        Some(index) => text[index + KEY.len()..]
+            .split(|c: char| c == '&' || c == '#' || c.is_whitespace())
+            .next()
+            .unwrap_or_default(),
+        None if !text.contains(|c: char| "/?=& ".contains(c)) => text,
+        None => "",
+    };
+    if verifier.is_empty() {
+        None
+    } else {
+        Some(verifier.to_owned())
     }
 }
 
+/// Parse `key0=value0&key1=value1&...`
+fn parse_pairs(text: &str) -> Vec<(&str, &str)> {
+    text.split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .collect()
+}
+
 fn get_value<'a>(
     iterator: &mut impl Iterator<Item = (&'a str, &'a str)>,
     find_key: &str,
@@ -387,6 +722,77 @@ mod tests {
         assert_eq!(oauth_signature.as_str(), "74KNZJeDHnMBp0EMJ9ZHt%2FXKycU%3D");
     }
 
+    #[test]
+    fn verifier() {
+        assert_eq!(
+            parse_verifier("GET /callback?oauth_token=abc&oauth_verifier=xyz#_=_ HTTP/1.1\r\n"),
+            Some("xyz".to_owned())
+        );
+        assert_eq!(
+            parse_verifier("http://127.0.0.1:1234/callback?oauth_verifier=xyz&oauth_token=abc"),
+            Some("xyz".to_owned())
+        );
+        assert_eq!(parse_verifier(" xyz\n"), Some("xyz".to_owned()));
+        assert_eq!(parse_verifier("GET /favicon.ico HTTP/1.1\r\n"), None);
+        assert_eq!(parse_verifier("\n"), None);
+    }
+
+    #[test]
+    fn update_fields() -> Result<()> {
+        let post = post::Post::new("---\ntitle: title\ntags: [tag0]\n---\nbody")?;
+        let url = "https://server.io/canonical/url.html".to_owned();
+        let settings = Settings {
+            update_fields: vec![UpdateField::Title],
+            ..Default::default()
+        };
+        let request = LinkRequest::new(&post, url.clone()).for_update(&settings);
+        assert_eq!(request.title, Some("title".to_owned()));
+        assert_eq!(request.url, url);
+        assert!(request.tags.is_none());
+        assert!(request.slug.is_none());
+        assert!(request.state.is_none());
+
+        let request = LinkRequest::new(&post, url).for_update(&Settings::default());
+        assert_eq!(request.state, Some("published".to_owned()));
+        assert_eq!(request.tags.unwrap().tags, vec!["tag0".to_owned()]);
+        Ok(())
+    }
+
+    #[test]
+    fn tags() -> Result<()> {
+        let post = post::Post::new(
+            "---\ntitle: title\ntags: [\"#rust lang\", \"a,b\"]\ncategories: blog\n---\nbody",
+        )?;
+        let request = LinkRequest::new(&post, "https://server.io".to_owned());
+        assert_eq!(
+            request.tags.unwrap().to_string(),
+            "rust lang,a b,blog".to_owned()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn queue() {
+        let mut post: post::Post = Default::default();
+        post.front_matter.date = Some(post::Date::parse("2999-01-01 10:00:00 +0000").unwrap());
+        let request = LinkRequest::new(&post, String::new());
+        assert_eq!(request.state, Some("queue".to_owned()));
+        assert_eq!(
+            request.publish_on,
+            Some("2999-01-01T10:00:00+00:00".to_owned())
+        );
+        assert!(request.date.is_none());
+
+        post.front_matter.date = Some(post::Date::parse("2021-05-03 10:00:00 +0900").unwrap());
+        let request = LinkRequest::new(&post, String::new());
+        assert_eq!(request.state, Some("published".to_owned()));
+        assert_eq!(request.date, Some("2021-05-03 01:00:00 GMT".to_owned()));
+
+        post.front_matter.published = Some(false);
+        let request = LinkRequest::new(&post, String::new());
+        assert_eq!(request.state, Some("draft".to_owned()));
+    }
+
     #[test]
     fn existing() -> Result<()> {
         let path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
@@ -403,9 +809,36 @@ mod tests {
             post
         };
         assert_eq!(
-            Tumblr::find_existing(&post, &posts).unwrap(),
+            Tumblr::match_existing(&post, &posts.response.posts)
+                .unwrap()
+                .id,
             "655788057293963264"
         );
         Ok(())
     }
+
+    #[test]
+    fn listed() -> Result<()> {
+        let path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("tumblr_posts.json.zst");
+        let file = std::fs::File::open(path)?;
+        let posts: Posts = serde_json::from_slice(&zstd::decode_all(file)?)?;
+        let articles: Vec<ListedArticle> = posts
+            .response
+            .posts
+            .into_iter()
+            .map(ListedArticle::from)
+            .collect();
+        let article = articles
+            .iter()
+            .find(|article| article.id == "655788057293963264")
+            .unwrap();
+        assert_eq!(
+            article.canonical_url.as_deref(),
+            Some("https://rendered-obsolete.github.io/2021/05/03/dotnet_calli.html")
+        );
+        assert!(article.title.is_some());
+        Ok(())
+    }
 }
@@ -8,7 +8,9 @@ pub struct FrontMatter {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub canonical_url: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub date: Option<String>,
+    pub cover_image: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<Date>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -21,8 +23,115 @@ pub struct FrontMatter {
     pub series: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub slug: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Jekyll allows a list or a space-separated string
+    #[serde(
+        default,
+        deserialize_with = "string_or_list",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub tags: Option<Vec<String>>,
+    /// Jekyll categories, used as extra tags
+    #[serde(
+        default,
+        deserialize_with = "string_or_list",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub categories: Option<Vec<String>>,
+    /// Jekyll's single category
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    /// Where the post was published on each platform, written by `--write-back`
+    #[serde(default, skip_serializing_if = "std::collections::BTreeMap::is_empty")]
+    pub bullhorn: std::collections::BTreeMap<String, Crosspost>,
+
+    /// Fields bullhorn doesn't use (e.g. `author`, `mathjax`), kept so they're written back
+    #[serde(flatten)]
+    pub extra: serde_yaml::Mapping,
+    /// Keys in the order they were read, so fields are written back in the same order
+    #[serde(skip)]
+    order: Vec<String>,
+    /// Lists that were read as space-separated strings, so they're written back that way
+    #[serde(skip)]
+    spaced: Vec<String>,
+}
+
+/// Article a post was cross-posted as
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Crosspost {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+/// Front-matter `date`, parsed when it's read and written back as it was read
+#[derive(Clone, Debug, PartialEq)]
+pub struct Date {
+    text: String,
+    parsed: chrono::DateTime<chrono::FixedOffset>,
+}
+
+impl Date {
+    pub fn new(date: &chrono::DateTime<chrono::FixedOffset>) -> Self {
+        Self {
+            text: dates::to_front_matter(date),
+            parsed: *date,
+        }
+    }
+
+    pub fn parse(text: &str) -> Result<Self> {
+        Ok(Self {
+            text: text.to_owned(),
+            parsed: dates::parse(text)?,
+        })
+    }
+
+    pub fn parsed(&self) -> chrono::DateTime<chrono::FixedOffset> {
+        self.parsed
+    }
+}
+
+impl std::fmt::Display for Date {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+impl Serialize for Date {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.text)
+    }
+}
+
+impl<'de> Deserialize<'de> for Date {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let text = String::deserialize(deserializer)?;
+        Self::parse(&text).map_err(|err| {
+            serde::de::Error::custom(format!("Invalid front-matter `date`: {:#}", err))
+        })
+    }
+}
+
+/// Format of the front matter, kept so posts are written back the way they were read
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    /// Delimited by `---` (Jekyll, Hugo)
+    Yaml,
+    /// Delimited by `+++` (Hugo)
+    Toml,
+    /// JSON object at the start of the file (Hugo)
+    Json,
+}
+
+impl Default for Format {
+    fn default() -> Self {
+        Format::Yaml
+    }
 }
 
 #[derive(Clone, Debug, Default)]
@@ -30,24 +139,473 @@ pub struct Post {
     pub front_matter: FrontMatter,
     pub body: String,
     pub path: std::path::PathBuf,
+    pub format: Format,
 }
 
 impl FrontMatter {
     const IS_PUBLISHED: bool = true;
     pub fn new(text: &str) -> Result<Self> {
-        let mut fm: FrontMatter = serde_yaml::from_str(text)?;
+        Self::parse(text, Format::Yaml)
+    }
+
+    pub fn parse(text: &str, format: Format) -> Result<Self> {
+        let mut fm: FrontMatter = match format {
+            Format::Yaml => serde_yaml::from_str(text)?,
+            Format::Toml => from_toml(text)?,
+            Format::Json => serde_json::from_str(text)?,
+        };
         if fm.slug.is_none() {
             fm.slug = Some(slug::slugify(&fm.title));
         }
         if fm.published.is_none() {
             fm.published = Some(Self::IS_PUBLISHED);
         }
+        let fields = read_fields(text, format)?;
+        for (key, value) in fields {
+            if let Some(key) = key.as_str() {
+                if value.is_string() && LISTS.contains(&key) {
+                    fm.spaced.push(key.to_owned());
+                }
+                fm.order.push(key.to_owned());
+            }
+        }
         Ok(fm)
     }
 
+    /// Fields in the order they were read, followed by any that weren't
+    fn to_mapping(&self) -> Result<serde_yaml::Mapping> {
+        let fields = match serde_yaml::to_value(self)? {
+            serde_yaml::Value::Mapping(fields) => fields,
+            _ => Default::default(),
+        };
+        let is_read = |key: &str| self.order.iter().any(|read| read == key);
+        // Defaults filled in when reading aren't written
+        let is_default = |key: &str| match key {
+            "slug" => self.slug == Some(slug::slugify(&self.title)),
+            "published" => self.published == Some(Self::IS_PUBLISHED),
+            _ => false,
+        };
+
+        let mut mapping = serde_yaml::Mapping::new();
+        for key in &self.order {
+            let value = fields.get(&serde_yaml::Value::String(key.clone()));
+            if let Some(value) = value {
+                let value = match value.as_sequence() {
+                    Some(list) if self.spaced.contains(key) => serde_yaml::Value::String(
+                        list.iter()
+                            .filter_map(serde_yaml::Value::as_str)
+                            .collect::<Vec<_>>()
+                            .join(" "),
+                    ),
+                    _ => value.clone(),
+                };
+                mapping.insert(serde_yaml::Value::String(key.clone()), value);
+            }
+        }
+        for (key, value) in fields {
+            let is_skipped = key
+                .as_str()
+                .map_or(false, |key| is_read(key) || is_default(key));
+            if !is_skipped {
+                mapping.insert(key, value);
+            }
+        }
+        Ok(mapping)
+    }
+
+    /// `tags`, `categories`, and `category` combined, without duplicates
+    pub fn all_tags(&self) -> Vec<String> {
+        let mut all_tags: Vec<String> = vec![];
+        let tags = self.tags.iter().flatten();
+        let categories = self.categories.iter().flatten();
+        for tag in tags.chain(categories).chain(self.category.iter()) {
+            if !all_tags.contains(tag) {
+                all_tags.push(tag.clone());
+            }
+        }
+        all_tags
+    }
+
     pub fn is_published(&self) -> bool {
         self.published.unwrap_or(Self::IS_PUBLISHED)
     }
+
+    /// Parsed `date`, if set
+    pub fn parsed_date(&self) -> Option<chrono::DateTime<chrono::FixedOffset>> {
+        self.date.as_ref().map(Date::parsed)
+    }
+
+    /// `date` if it's later than `now`, i.e. the post shouldn't be published yet
+    pub fn scheduled_date(
+        &self,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Option<chrono::DateTime<chrono::FixedOffset>> {
+        self.parsed_date()
+            .filter(|date| date.with_timezone(&chrono::Utc) > now)
+    }
+}
+
+/// Fields that may be a list or a space-separated string
+const LISTS: &[&str] = &["tags", "categories"];
+
+/// Top-level fields of front matter `text`, in order
+fn read_fields(text: &str, format: Format) -> Result<serde_yaml::Mapping> {
+    let fields = match format {
+        Format::Yaml => {
+            serde_yaml::from_str::<Option<serde_yaml::Mapping>>(text)?.unwrap_or_default()
+        }
+        Format::Toml => serde_yaml::to_value(toml::from_str::<toml::value::Table>(text)?)?
+            .as_mapping()
+            .cloned()
+            .unwrap_or_default(),
+        Format::Json => serde_json::from_str::<serde_yaml::Mapping>(text)?,
+    };
+    Ok(fields)
+}
+
+/// List from either a list or a space-separated string, e.g. `tags: [a, b]` or `tags: a b`
+fn string_or_list<'de, D>(deserializer: D) -> Result<Option<Vec<String>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    use serde::de::Error;
+    use serde_yaml::Value;
+
+    // Numbers and booleans are used as they're written, e.g. `tags: [2021, rust]`
+    let to_string = |value: Value| match value {
+        Value::String(text) => Ok(text),
+        Value::Number(number) => Ok(number.to_string()),
+        Value::Bool(value) => Ok(value.to_string()),
+        _ => Err(D::Error::custom("expected a string or list of strings")),
+    };
+    let list = match Option::<Value>::deserialize(deserializer)? {
+        None | Some(Value::Null) => None,
+        Some(Value::String(text)) => Some(text.split_whitespace().map(str::to_owned).collect()),
+        Some(Value::Sequence(list)) => {
+            Some(list.into_iter().map(to_string).collect::<Result<_, _>>()?)
+        }
+        Some(value) => Some(vec![to_string(value)?]),
+    };
+    Ok(list)
+}
+
+/// TOML front matter, with dates (which TOML can't deserialize into strings) as text
+fn from_toml(text: &str) -> Result<FrontMatter> {
+    let mut value: toml::Value = toml::from_str(text)?;
+    if let Some(table) = value.as_table_mut() {
+        for (_, value) in table.iter_mut() {
+            if let toml::Value::Datetime(date) = value {
+                *value = toml::Value::String(date.to_string());
+            }
+        }
+    }
+    Ok(value.try_into()?)
+}
+
+/// TOML front matter, with dates written as TOML dates if possible
+fn to_toml(fields: &serde_yaml::Mapping) -> Result<String> {
+    // TOML has no null
+    let fields: serde_yaml::Mapping = fields
+        .iter()
+        .filter(|(_, value)| !value.is_null())
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    let mut value = toml::Value::try_from(&fields)?;
+    if let Some(date) = value.get_mut("date") {
+        if let Some(datetime) = date.as_str().and_then(|date| date.parse().ok()) {
+            *date = toml::Value::Datetime(datetime);
+        }
+    }
+    Ok(toml::to_string(&value)?)
+}
+
+/// Set top-level `fields` in the front matter of post `text`, or remove those that are `None`.
+/// Nothing else is changed, so comments and formatting are kept.
+pub fn set_fields(text: &str, fields: &[(&str, Option<serde_yaml::Value>)]) -> Result<String> {
+    let (format, front_matter, _) = split(text)?;
+    // Front matter is a slice of `text`
+    let start = front_matter.as_ptr() as usize - text.as_ptr() as usize;
+    let end = start + front_matter.len();
+    let mut front_matter = front_matter.to_owned();
+    for (key, value) in fields {
+        let value = value.as_ref();
+        front_matter = match format {
+            Format::Yaml => set_yaml_field(&front_matter, key, value)?,
+            Format::Toml => set_toml_field(&front_matter, key, value)?,
+            Format::Json => set_json_field(&front_matter, key, value)?,
+        };
+    }
+    Ok(format!(
+        "{}{}{}",
+        &text[..start],
+        front_matter,
+        &text[end..]
+    ))
+}
+
+/// Replace the lines setting `key` in YAML `text`, or append them
+pub fn set_yaml_field(text: &str, key: &str, value: Option<&serde_yaml::Value>) -> Result<String> {
+    let entry = match value {
+        Some(value) => {
+            let mut entry = serde_yaml::Mapping::new();
+            entry.insert(key.into(), value.clone());
+            let entry = serde_yaml::to_string(&entry)?;
+            entry.trim_start_matches("---\n").trim_end().to_owned() + "\n"
+        }
+        None => String::new(),
+    };
+
+    let prefix = format!("{}:", key);
+    let lines: Vec<&str> = text.split_inclusive('\n').collect();
+    match lines.iter().position(|line| line.starts_with(&prefix)) {
+        Some(start) => {
+            // Value may continue on following lines, e.g. a block scalar or list
+            let mut end = start + 1;
+            for (index, line) in lines.iter().enumerate().skip(start + 1) {
+                if line.starts_with(&[' ', '\t', '-'][..]) {
+                    end = index + 1;
+                } else if !line.trim().is_empty() {
+                    break;
+                }
+            }
+            Ok(lines[..start].concat() + &entry + &lines[end..].concat())
+        }
+        None => {
+            let mut text = text.to_owned();
+            if !text.is_empty() && !text.ends_with('\n') {
+                text.push('\n');
+            }
+            Ok(text + &entry)
+        }
+    }
+}
+
+/// Replace `key` in TOML `text`.  Values are added after the other top-level values, and tables
+/// at the end.
+fn set_toml_field(text: &str, key: &str, value: Option<&serde_yaml::Value>) -> Result<String> {
+    let is_header = |line: &str| line.trim_start().starts_with('[');
+    let is_key_header = |line: &str| {
+        let name = line.trim().trim_matches(&['[', ']'][..]).trim();
+        name == key || name.starts_with(&format!("{}.", key))
+    };
+    let is_key_value = |line: &str| line.split('=').next().map(str::trim) == Some(key);
+    let is_blank = |line: &str| line.trim().is_empty() || line.trim_start().starts_with('#');
+
+    // Remove `key`, keeping blank lines and comments before the next table
+    let mut lines = vec![];
+    let mut in_tables = false;
+    let mut in_key_table = false;
+    let mut pending = vec![];
+    for line in text.split_inclusive('\n') {
+        if is_header(line) {
+            in_tables = true;
+            in_key_table = is_key_header(line);
+            if !in_key_table {
+                lines.append(&mut pending);
+            }
+            pending.clear();
+        }
+        if in_key_table {
+            if is_blank(line) {
+                pending.push(line);
+            }
+        } else if in_tables || !is_key_value(line) {
+            lines.push(line);
+        }
+    }
+    lines.append(&mut pending);
+    if in_key_table {
+        // Table was last, so there's nothing to separate from it
+        while matches!(lines.last(), Some(line) if line.trim().is_empty()) {
+            lines.pop();
+        }
+    }
+
+    let value = match value {
+        Some(value) => value,
+        None => return Ok(lines.concat()),
+    };
+    let mut value = toml::Value::try_from(value)?;
+    // Written as a TOML date if possible, same as `to_toml`
+    if key == "date" {
+        if let Some(datetime) = value.as_str().and_then(|date| date.parse().ok()) {
+            value = toml::Value::Datetime(datetime);
+        }
+    }
+    let is_table = value.is_table();
+    let mut entry = toml::value::Table::new();
+    entry.insert(key.to_owned(), value);
+    let entry = toml::to_string(&entry)?;
+
+    let mut text = lines.concat();
+    if !text.is_empty() && !text.ends_with('\n') {
+        text.push('\n');
+    }
+    match lines.iter().position(|line| is_header(line)) {
+        // After the last value before the first table
+        Some(first_table) if !is_table => {
+            let index = lines[..first_table]
+                .iter()
+                .rposition(|line| !is_blank(line))
+                .map_or(0, |index| index + 1);
+            Ok(lines[..index].concat() + &entry + &lines[index..].concat())
+        }
+        _ if is_table && !text.is_empty() && !text.ends_with("\n\n") => Ok(text + "\n" + &entry),
+        _ => Ok(text + &entry),
+    }
+}
+
+/// Replace member `key` of the JSON object `text`, or add it after the last member
+fn set_json_field(text: &str, key: &str, value: Option<&serde_yaml::Value>) -> Result<String> {
+    let members = json_members(text)?;
+    let entry = match value {
+        Some(value) => Some(format!(
+            "{}: {}",
+            serde_json::to_string(key)?,
+            serde_json::to_string(value)?
+        )),
+        None => None,
+    };
+    let index = members.iter().position(|member| member.0 == key);
+    let text = match (index, entry) {
+        (Some(index), Some(entry)) => {
+            let (_, start, end) = &members[index];
+            format!("{}{}{}", &text[..*start], entry, &text[*end..])
+        }
+        (Some(index), None) => {
+            // Remove the separating comma too
+            let (start, end) = match (members.get(index + 1), index.checked_sub(1)) {
+                (Some(next), _) => (members[index].1, next.1),
+                (None, Some(previous)) => (members[previous].2, members[index].2),
+                (None, None) => (members[index].1, members[index].2),
+            };
+            format!("{}{}", &text[..start], &text[end..])
+        }
+        (None, Some(entry)) => match (members.first(), members.last()) {
+            (Some(first), Some(last)) => {
+                // Indented like the first member
+                let open = text[..first.1].rfind('{').unwrap_or_default();
+                let indent = &text[open + 1..first.1];
+                format!("{},{}{}{}", &text[..last.2], indent, entry, &text[last.2..])
+            }
+            _ => {
+                let open = text.find('{').unwrap_or_default();
+                format!("{}{}{}", &text[..=open], entry, &text[open + 1..])
+            }
+        },
+        (None, None) => text.to_owned(),
+    };
+    Ok(text)
+}
+
+/// Top-level members of the JSON object `text`, with where each starts and ends
+fn json_members(text: &str) -> Result<Vec<(String, usize, usize)>> {
+    let bytes = text.as_bytes();
+    let mut members = vec![];
+    let mut current: Option<(String, usize)> = None;
+    let mut depth = 0;
+    let mut is_key = false;
+    let mut index = 0;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'"' => {
+                let start = index;
+                index += 1;
+                while index < bytes.len() && bytes[index] != b'"' {
+                    index += if bytes[index] == b'\\' { 2 } else { 1 };
+                }
+                if depth == 1 && is_key {
+                    let key = serde_json::from_str(&text[start..=index])?;
+                    current = Some((key, start));
+                    is_key = false;
+                }
+            }
+            b'{' | b'[' => {
+                depth += 1;
+                is_key = depth == 1;
+            }
+            b',' | b'}' | b']' if depth == 1 => {
+                if let Some((key, start)) = current.take() {
+                    members.push((key, start, text[..index].trim_end().len()));
+                }
+                is_key = bytes[index] == b',';
+                if bytes[index] != b',' {
+                    depth -= 1;
+                }
+            }
+            b'}' | b']' => depth -= 1,
+            _ => {}
+        }
+        index += 1;
+    }
+    Ok(members)
+}
+
+/// Whether `text` starts with front matter, e.g. to tell posts from other markdown files
+pub fn has_front_matter(text: &str) -> bool {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let opening = text.lines().next().unwrap_or_default().trim_end();
+    text.starts_with('{') || opening == "---" || opening == "+++"
+}
+
+/// Split `text` into its format, front matter, and body.  Front matter must be at the very start
+/// (after an optional byte order mark) and ends at the first line matching the opening delimiter.
+fn split(text: &str) -> Result<(Format, &str, &str)> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    if text.starts_with('{') {
+        let mut values = serde_json::Deserializer::from_str(text).into_iter::<serde_json::Value>();
+        // If it isn't valid JSON, parse it all to report the error
+        let end = match values.next() {
+            Some(Ok(_)) => values.byte_offset(),
+            _ => text.len(),
+        };
+        let (front_matter, rest) = text.split_at(end);
+        // Body starts on the line after the object
+        let rest = rest.trim_start_matches(&[' ', '\t', '\r'][..]);
+        let body = rest.strip_prefix('\n').unwrap_or(rest);
+        return Ok((Format::Json, front_matter, body));
+    }
+
+    let mut lines = text.split_inclusive('\n');
+    let opening = lines.next().unwrap_or_default();
+    let delimiter = opening.trim_end();
+    let format = match delimiter {
+        "---" => Format::Yaml,
+        "+++" => Format::Toml,
+        _ => {
+            return Err(Error::BadFormat {
+                thing: "no front-matter, must start with `---`, `+++`, or `{`".to_owned(),
+            }
+            .into())
+        }
+    };
+    let start = opening.len();
+    let mut end = start;
+    for line in lines {
+        // Delimiters may have trailing white-space
+        if line.trim_end() == delimiter {
+            return Ok((format, &text[start..end], &text[end + line.len()..]));
+        }
+        end += line.len();
+    }
+    Err(Error::BadFormat {
+        thing: format!("no closing `{}` after front-matter", delimiter),
+    }
+    .into())
+}
+
+/// Line of a front-matter parsing error, counting from the first line of front matter
+fn error_line(err: &anyhow::Error) -> Option<usize> {
+    if let Some(err) = err.downcast_ref::<serde_yaml::Error>() {
+        return err.location().map(|location| location.line());
+    }
+    if let Some(err) = err.downcast_ref::<toml::de::Error>() {
+        return err.line_col().map(|(line, _)| line + 1);
+    }
+    if let Some(err) = err.downcast_ref::<serde_json::Error>() {
+        return Some(err.line()).filter(|line| *line > 0);
+    }
+    None
 }
 
 impl Post {
@@ -56,27 +614,23 @@ impl Post {
     }
 
     pub fn from_string(text: String) -> Result<Self> {
-        // Multi-line mode, at least 2 dashes, any trailing white-space
-        let re = regex::Regex::new(r"(?m)^--+\s*")?;
-        // Split at dashes into: before front-matter (nothing), front-matter, and body
-        let mut matches = re.splitn(&text, 3);
-        matches.next(); // Skip the split before the first dashes
-        let front_matter = {
-            let text = matches.next().ok_or(Error::BadFormat {
-                thing: "no front-matter".to_owned(),
-            })?;
-            FrontMatter::new(text)?
-        };
-        let body = matches
-            .next()
-            .map(|s| s.to_owned())
-            .ok_or(Error::BadFormat {
-                thing: "no body".to_owned(),
-            })?;
+        let (format, front_matter, body) = split(&text)?;
+        let front_matter = FrontMatter::parse(front_matter, format).map_err(|err| {
+            // Front matter starts on the line after the opening delimiter, except for JSON
+            let first_line = if format == Format::Json { 0 } else { 1 };
+            match error_line(&err) {
+                Some(line) => err.context(format!(
+                    "Invalid front-matter at line {}",
+                    first_line + line
+                )),
+                None => err,
+            }
+        })?;
         Ok(Post {
-            body,
+            body: body.to_owned(),
             front_matter,
             path: Default::default(),
+            format,
         })
     }
 
@@ -94,12 +648,27 @@ impl Post {
     }
 
     pub fn to_string(&self) -> Result<String> {
-        let mut str = serde_yaml::to_string(&self.front_matter)?;
-        str.push_str("---\n");
+        let fields = self.front_matter.to_mapping()?;
+        let mut str = match self.format {
+            // Starts with `---`
+            Format::Yaml => serde_yaml::to_string(&fields)? + "---\n",
+            Format::Toml => format!("+++\n{}+++\n", to_toml(&fields)?),
+            Format::Json => serde_json::to_string_pretty(&fields)? + "\n",
+        };
         str.push_str(&self.body);
         Ok(str)
     }
 
+    /// Hash of the rendered post, used to detect changes since it was last published
+    pub fn content_hash(&self) -> Result<String> {
+        use sha2::Digest;
+        // `bullhorn` is written back after publishing, so isn't a change to the post
+        let mut post = self.clone();
+        post.front_matter.bullhorn.clear();
+        let text = post.to_string()?;
+        Ok(format!("{:x}", sha2::Sha256::digest(text.as_bytes())))
+    }
+
     pub fn apply(&mut self, settings: &Settings) {
         if settings.draft {
             self.front_matter.published = Some(false);
@@ -107,6 +676,9 @@ impl Post {
         if let Some(slug) = &settings.slug {
             self.front_matter.slug = Some(slug.clone());
         }
+        if let Some(date) = &settings.date {
+            self.front_matter.date = Some(Date::new(date));
+        }
     }
 }
 
@@ -224,6 +796,15 @@ tags: [tag0, tag1]
 body";
         let post = Post::new(array).unwrap();
         assert_eq!(post.front_matter.tags, multiple_tags);
+
+        let scalars = "---
+title: title
+tags: [2021, rust, true]
+---
+body";
+        let post = Post::new(scalars).unwrap();
+        let expected = vec!["2021".to_owned(), "rust".to_owned(), "true".to_owned()];
+        assert_eq!(post.front_matter.tags, Some(expected));
     }
 
     #[test]
@@ -236,6 +817,42 @@ body";
         let post = Post::new(text).unwrap();
         assert_eq!(post.front_matter.title, "title");
         assert_eq!(post.body, "body");
+        assert_eq!(
+            post.front_matter.extra.get(&"bad_field".into()),
+            Some(&"very naughty".into())
+        );
+    }
+
+    #[test]
+    fn unknown_fields() -> Result<()> {
+        let text = "---
+author: author
+title: title
+categories:
+  - category0
+mathjax: true
+tags:
+  - tag0
+comments: false
+---
+body";
+        let mut post = Post::new(text)?;
+        assert_eq!(post.to_string()?, text);
+
+        // Fields that weren't read are added at the end
+        post.front_matter.canonical_url = Some("https://server.io/url.html".to_owned());
+        assert!(post.to_string()?.ends_with(
+            "comments: false\ncanonical_url: \"https://server.io/url.html\"\n---\nbody"
+        ));
+
+        for text in &[
+            "+++\nauthor = \"author\"\ntitle = \"title\"\nmathjax = true\n+++\nbody",
+            "{\n  \"author\": \"author\",\n  \"title\": \"title\",\n  \"mathjax\": true\n}\nbody",
+        ] {
+            let post = Post::new(text)?;
+            assert_eq!(&post.to_string()?, text);
+        }
+        Ok(())
     }
 
     #[test]
@@ -266,6 +883,218 @@ title: title
         assert!(!post.body.is_empty());
     }
 
+    #[test]
+    fn scheduled_date() {
+        use chrono::TimeZone;
+
+        let now = chrono::Utc.with_ymd_and_hms(2021, 5, 3, 12, 0, 0).unwrap();
+        let scheduled = |date: &str| {
+            let front_matter = FrontMatter {
+                date: Some(Date::parse(date).unwrap()),
+                ..Default::default()
+            };
+            front_matter
+                .scheduled_date(now)
+                .map(|date| dates::to_iso8601(&date))
+        };
+        assert_eq!(scheduled("2021-05-02"), None);
+        assert_eq!(scheduled("2021-05-03 11:00:00"), None);
+        assert_eq!(
+            scheduled("2021-05-03 22:00:00 +0900"),
+            Some("2021-05-03T22:00:00+09:00".to_owned())
+        );
+        assert_eq!(FrontMatter::default().scheduled_date(now), None);
+    }
+
+    #[test]
+    fn date() {
+        let post = Post::new("---\ntitle: title\ndate: 2021-05-03 10:00:00 +0900\n---\nbody");
+        assert!(post.is_ok());
+        let err = Post::new("---\ntitle: title\ndate: not a date\n---\nbody").unwrap_err();
+        assert!(format!("{:#}", err).contains("Invalid front-matter `date`"));
+
+        let mut post = post.unwrap();
+        let settings = Settings {
+            date: Some(dates::parse("2021-05-04T10:00:00Z").unwrap()),
+            ..Default::default()
+        };
+        post.apply(&settings);
+        assert_eq!(
+            post.front_matter.date.as_ref().map(Date::to_string),
+            Some("2021-05-04 10:00:00 +0000".to_owned())
+        );
+    }
+
+    #[test]
+    fn fields() -> Result<()> {
+        let mut bullhorn = serde_yaml::Mapping::new();
+        bullhorn.insert(
+            "devto".into(),
+            serde_yaml::to_value(Crosspost {
+                id: "1".to_owned(),
+                url: None,
+            })?,
+        );
+        let bullhorn = Some(serde_yaml::Value::Mapping(bullhorn));
+        let added = |text: &str| -> Result<String> {
+            set_fields(
+                text,
+                &[
+                    ("canonical_url", Some("url".into())),
+                    ("bullhorn", bullhorn.clone()),
+                ],
+            )
+        };
+        let removed = |text: &str| set_fields(text, &[("bullhorn", None)]);
+
+        let text = "+++\ntitle = \"title\" # comment\n\n[extra]\na = 1\n+++\nbody";
+        let with_fields = added(text)?;
+        assert_eq!(
+            with_fields,
+            "+++\ntitle = \"title\" # comment\ncanonical_url = \"url\"\n\n[extra]\na = 1\n\n\
+             [bullhorn.devto]\nid = \"1\"\n+++\nbody"
+        );
+        assert_eq!(
+            Post::new(&with_fields)?.front_matter.bullhorn["devto"].id,
+            "1"
+        );
+        assert_eq!(
+            removed(&with_fields)?,
+            "+++\ntitle = \"title\" # comment\ncanonical_url = \"url\"\n\n[extra]\na = 1\n+++\nbody"
+        );
+
+        let text = "{\n  \"title\": \"title\",\n  \"tags\": [\"a\", \"b\"]\n}\nbody";
+        let with_fields = added(text)?;
+        assert_eq!(
+            with_fields,
+            "{\n  \"title\": \"title\",\n  \"tags\": [\"a\", \"b\"],\n  \"canonical_url\": \"url\",\n  \
+             \"bullhorn\": {\"devto\":{\"id\":\"1\"}}\n}\nbody"
+        );
+        assert_eq!(
+            Post::new(&with_fields)?.front_matter.bullhorn["devto"].id,
+            "1"
+        );
+        assert_eq!(
+            removed(&with_fields)?,
+            "{\n  \"title\": \"title\",\n  \"tags\": [\"a\", \"b\"],\n  \"canonical_url\": \"url\"\n}\nbody"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn toml() -> Result<()> {
+        let text = "+++
+title = \"title\"
+date = 2021-05-03T10:00:00+09:00
+tags = [\"tag0\", \"tag1\"]
++++
+body";
+        let post = Post::new(text)?;
+        assert_eq!(post.format, Format::Toml);
+        assert_eq!(post.front_matter.title, "title");
+        assert_eq!(
+            post.front_matter.date.as_ref().map(Date::to_string),
+            Some("2021-05-03T10:00:00+09:00".to_owned())
+        );
+        assert_eq!(post.front_matter.tags.as_ref().unwrap().len(), 2);
+        assert_eq!(post.body, "body");
+
+        let text = post.to_string()?;
+        assert!(text.starts_with("+++\ntitle = \"title\"\n"));
+        assert!(text.contains("\ndate = 2021-05-03T10:00:00+09:00\n"));
+        let round_trip = Post::new(&text)?;
+        assert_eq!(round_trip.front_matter.date, post.front_matter.date);
+        assert_eq!(round_trip.body, post.body);
+        Ok(())
+    }
+
+    #[test]
+    fn json() -> Result<()> {
+        let text = "{
+  \"title\": \"title\",
+  \"tags\": [\"tag0\"]
+}
+
+body";
+        let post = Post::new(text)?;
+        assert_eq!(post.format, Format::Json);
+        assert_eq!(post.front_matter.title, "title");
+        assert_eq!(post.body, "\nbody");
+
+        let round_trip = Post::new(&post.to_string()?)?;
+        assert_eq!(round_trip.format, Format::Json);
+        assert_eq!(round_trip.front_matter.tags, post.front_matter.tags);
+        assert_eq!(round_trip.body, post.body);
+        Ok(())
+    }
+
+    #[test]
+    fn delimiters() -> Result<()> {
+        // Byte order mark
+        let post = Post::new("\u{feff}---\ntitle: title\n---\nbody")?;
+        assert_eq!(post.front_matter.title, "title");
+        assert_eq!(post.body, "body");
+
+        // Only the first closing `---` ends front matter, other dashes are part of the body
+        let body = "SELECT 1;\n-- comment\n--\n\n---\n";
+        let post = Post::new(&format!("---\ntitle: title\n---\n{}", body))?;
+        assert_eq!(post.body, body);
+
+        for text in &[
+            "-- comment\ntitle: title\n---\nbody",
+            "# Heading\n\n---\ntitle: title\n---\nbody",
+            "----\ntitle: title\n----\nbody",
+            "---\ntitle: title\n+++\nbody",
+        ] {
+            let _ = Post::new(text).unwrap_err();
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn error_line() {
+        let err = Post::new("---\ntitle: title\ndescription: a: b\n---\nbody").unwrap_err();
+        assert!(format!("{:#}", err).starts_with("Invalid front-matter at line 3"));
+
+        let err = Post::new("+++\ntitle = \"title\"\ntags = [\n+++\nbody").unwrap_err();
+        assert!(format!("{:#}", err).starts_with("Invalid front-matter at line 4"));
+
+        let err = Post::new("{\n  \"title\": \"title\",\n}\nbody").unwrap_err();
+        assert!(format!("{:#}", err).starts_with("Invalid front-matter at line 3"));
+    }
+
+    #[test]
+    fn categories() -> Result<()> {
+        let text = "---
+title: title
+tags: tag0 tag1
+categories:
+- tag1
+- category0
+category: category1
+---
+body";
+        let post = Post::new(text)?;
+        let tags = vec!["tag0".to_owned(), "tag1".to_owned()];
+        assert_eq!(post.front_matter.tags, Some(tags));
+        assert_eq!(
+            post.front_matter.all_tags(),
+            vec!["tag0", "tag1", "category0", "category1"]
+        );
+        // Space-separated strings are written back as strings
+        assert_eq!(post.to_string()?, text.replace("- ", "  - "));
+
+        let post = Post::new("+++\ntitle = \"title\"\ncategories = \"a b\"\n+++\nbody")?;
+        assert_eq!(post.front_matter.all_tags(), vec!["a", "b"]);
+        let post = Post::new("{\"title\": \"title\", \"tags\": \"a\"}\nbody")?;
+        assert_eq!(post.front_matter.all_tags(), vec!["a"]);
+        assert!(Post::new("---\ntitle: title\n---\nbody")?
+            .front_matter
+            .all_tags()
+            .is_empty());
+        Ok(())
+    }
+
     #[test]
     fn comment() {
         let text = "---
@@ -0,0 +1,391 @@
+use crate::{platforms::*, post::Post, *};
+
+/// Outcome of publishing one post to one platform
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct Entry {
+    pub post: std::path::PathBuf,
+    pub title: String,
+    pub canonical_url: Option<String>,
+    #[serde(flatten)]
+    pub outcome: PublishOutcome,
+}
+
+/// Outcomes of all posts published in a run
+#[derive(Clone, Debug, Default, serde::Serialize)]
+pub struct Summary {
+    #[serde(rename = "results")]
+    pub entries: Vec<Entry>,
+}
+
+impl Summary {
+    pub fn add(&mut self, post: &Post, outcomes: Vec<PublishOutcome>) {
+        self.entries
+            .extend(outcomes.into_iter().map(|outcome| Entry {
+                post: post.path.clone(),
+                title: post.front_matter.title.clone(),
+                canonical_url: post.front_matter.canonical_url.clone(),
+                outcome,
+            }));
+    }
+
+    /// Outcomes for a post that couldn't be opened
+    pub fn add_failed(&mut self, path: &std::path::Path, outcomes: Vec<PublishOutcome>) {
+        self.entries
+            .extend(outcomes.into_iter().map(|outcome| Entry {
+                post: path.to_owned(),
+                title: String::new(),
+                canonical_url: None,
+                outcome,
+            }));
+    }
+
+    pub fn render(&self, format: &ReportFormat) -> Result<String> {
+        match format {
+            ReportFormat::Table => Ok(self.to_previews() + &self.to_table()),
+            ReportFormat::Json => Ok(serde_json::to_string_pretty(self)? + "\n"),
+        }
+    }
+
+    /// Write report to `settings.report_file`, or stdout if not set
+    pub fn write(&self, settings: &Settings) -> Result<()> {
+        let report = self.render(&settings.report)?;
+        write(settings, &report)
+    }
+
+    /// `false` if publishing to any platform failed
+    pub fn is_success(&self) -> bool {
+        self.entries
+            .iter()
+            .all(|entry| entry.outcome.status != Status::Failed)
+    }
+
+    /// What each platform would have done during a dry run
+    pub fn to_previews(&self) -> String {
+        let mut text = String::new();
+        for entry in &self.entries {
+            let outcome = &entry.outcome;
+            if let Some(preview) = &outcome.preview {
+                let action = match preview.action {
+                    Status::Created => "create",
+                    Status::Deleted => "delete",
+                    _ => "update",
+                };
+                text.push_str(&format!(
+                    "{}: would {} {}",
+                    outcome.platform,
+                    action,
+                    entry.post.display()
+                ));
+                if let Some(id) = &outcome.remote_id {
+                    text.push_str(&format!(" (id={})", id));
+                }
+                text.push('\n');
+                match &preview.diff {
+                    Some(diff) if diff.is_empty() => text.push_str("No changes\n"),
+                    Some(diff) => text.push_str(diff),
+                    None => text.push_str("Unable to get current article\n"),
+                }
+                text.push('\n');
+            }
+        }
+        text
+    }
+
+    pub fn to_table(&self) -> String {
+        let header = ["POST", "PLATFORM", "STATUS", "ID", "DETAILS"];
+        let rows: Vec<Vec<String>> = self
+            .entries
+            .iter()
+            .map(|entry| {
+                let outcome = &entry.outcome;
+                vec![
+                    entry.post.display().to_string(),
+                    outcome.platform.to_owned(),
+                    outcome.status.to_string(),
+                    outcome.remote_id.clone().unwrap_or_default(),
+                    outcome
+                        .error
+                        .clone()
+                        .or_else(|| outcome.remote_url.clone())
+                        .unwrap_or_default(),
+                ]
+            })
+            .collect();
+        format_table(&header, &rows)
+    }
+}
+
+/// Whether a post is up to date on a platform
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SyncStatus {
+    Missing,
+    InSync,
+    OutOfDate,
+    /// Published, but the platform can't say whether it's up to date
+    Published,
+    /// Canonical URL doesn't match any post
+    Orphaned,
+    Failed,
+}
+
+impl std::fmt::Display for SyncStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        let status = match self {
+            SyncStatus::Missing => "missing",
+            SyncStatus::InSync => "in-sync",
+            SyncStatus::OutOfDate => "out-of-date",
+            SyncStatus::Published => "published",
+            SyncStatus::Orphaned => "orphaned",
+            SyncStatus::Failed => "failed",
+        };
+        write!(f, "{}", status)
+    }
+}
+
+/// Status of one post (or orphaned article) on one platform
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct StatusEntry {
+    /// `None` for orphaned articles
+    pub post: Option<std::path::PathBuf>,
+    pub platform: &'static str,
+    pub status: SyncStatus,
+    pub remote_id: Option<String>,
+    pub remote_url: Option<String>,
+    /// Fields that differ from the post
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub changed: Vec<&'static str>,
+    pub error: Option<String>,
+}
+
+pub fn render_status(entries: &[StatusEntry], format: &ReportFormat) -> Result<String> {
+    match format {
+        ReportFormat::Json => Ok(serde_json::to_string_pretty(entries)? + "\n"),
+        ReportFormat::Table => {
+            let header = ["POST", "PLATFORM", "STATUS", "ID", "DETAILS"];
+            let rows: Vec<Vec<String>> = entries
+                .iter()
+                .map(|entry| {
+                    let details = if entry.changed.is_empty() {
+                        entry
+                            .error
+                            .clone()
+                            .or_else(|| entry.remote_url.clone())
+                            .unwrap_or_default()
+                    } else {
+                        format!("changed: {}", entry.changed.join(", "))
+                    };
+                    vec![
+                        entry
+                            .post
+                            .as_ref()
+                            .map_or_else(|| "-".to_owned(), |post| post.display().to_string()),
+                        entry.platform.to_owned(),
+                        entry.status.to_string(),
+                        entry.remote_id.clone().unwrap_or_default(),
+                        details,
+                    ]
+                })
+                .collect();
+            Ok(format_table(&header, &rows))
+        }
+    }
+}
+
+/// Articles listed on a platform
+pub fn render_articles(articles: &[ListedArticle], format: &ReportFormat) -> Result<String> {
+    match format {
+        ReportFormat::Json => Ok(serde_json::to_string_pretty(articles)? + "\n"),
+        ReportFormat::Table => {
+            let header = [
+                "PLATFORM",
+                "ID",
+                "PUBLISHED",
+                "TITLE",
+                "SLUG",
+                "CANONICAL_URL",
+            ];
+            let rows: Vec<Vec<String>> = articles
+                .iter()
+                .map(|article| {
+                    let published = match article.published {
+                        Some(true) => "yes",
+                        Some(false) => "no",
+                        None => "",
+                    };
+                    vec![
+                        article.platform.to_owned(),
+                        article.id.clone(),
+                        published.to_owned(),
+                        article.title.clone().unwrap_or_default(),
+                        article.slug.clone().unwrap_or_default(),
+                        article.canonical_url.clone().unwrap_or_default(),
+                    ]
+                })
+                .collect();
+            Ok(format_table(&header, &rows))
+        }
+    }
+}
+
+/// Rows of text in columns padded to their widest value
+pub fn format_table(header: &[&str], rows: &[Vec<String>]) -> String {
+    let mut widths: Vec<usize> = header.iter().map(|h| h.len()).collect();
+    for row in rows {
+        for (width, value) in widths.iter_mut().zip(row) {
+            *width = (*width).max(value.len());
+        }
+    }
+    let format_row = |row: &[&str]| {
+        let line: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .map(|(value, width)| format!("{:width$}", value, width = width))
+            .collect();
+        format!("{}\n", line.join("  ").trim_end())
+    };
+
+    let mut table = format_row(header);
+    for row in rows {
+        let row: Vec<&str> = row.iter().map(String::as_str).collect();
+        table.push_str(&format_row(&row));
+    }
+    table
+}
+
+/// Write `text` to `settings.report_file`, or stdout if not set
+pub fn write(settings: &Settings, text: &str) -> Result<()> {
+    match &settings.report_file {
+        Some(path) => std::fs::write(path, text)
+            .with_context(|| format!("Unable to write report: {}", path))?,
+        None => print!("{}", text),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outcome(status: Status) -> PublishOutcome {
+        PublishOutcome {
+            platform: "devto",
+            status,
+            remote_id: Some("1234".to_owned()),
+            remote_url: Some("https://dev.to/user/title".to_owned()),
+            error: None,
+            preview: None,
+        }
+    }
+
+    #[test]
+    fn success() {
+        let post: Post = Default::default();
+        let mut summary = Summary::default();
+        assert!(summary.is_success());
+        summary.add(
+            &post,
+            vec![outcome(Status::Created), outcome(Status::Skipped)],
+        );
+        assert!(summary.is_success());
+        summary.add(&post, vec![outcome(Status::Failed)]);
+        assert!(!summary.is_success());
+    }
+
+    #[test]
+    fn json() -> Result<()> {
+        let mut post: Post = Default::default();
+        post.front_matter.title = "title".to_owned();
+        let mut summary = Summary::default();
+        summary.add(&post, vec![outcome(Status::Created)]);
+        let json: serde_json::Value = serde_json::from_str(&summary.render(&ReportFormat::Json)?)?;
+        let result = &json["results"][0];
+        assert_eq!(result["title"], "title");
+        assert_eq!(result["platform"], "devto");
+        assert_eq!(result["status"], "created");
+        assert_eq!(result["remote_id"], "1234");
+        assert_eq!(result["remote_url"], "https://dev.to/user/title");
+        Ok(())
+    }
+
+    #[test]
+    fn table() {
+        let post = Post {
+            path: std::path::PathBuf::from("2021-7-1-test.md"),
+            ..Default::default()
+        };
+        let mut summary = Summary::default();
+        summary.add(&post, vec![outcome(Status::Updated)]);
+        let table = summary.to_table();
+        let mut lines = table.lines();
+        assert!(lines.next().unwrap().starts_with("POST"));
+        assert_eq!(
+            lines.next().unwrap(),
+            "2021-7-1-test.md  devto     updated  1234  https://dev.to/user/title"
+        );
+    }
+
+    #[test]
+    fn status() -> Result<()> {
+        let entries = vec![
+            StatusEntry {
+                post: Some(std::path::PathBuf::from("post.md")),
+                platform: "devto",
+                status: SyncStatus::OutOfDate,
+                remote_id: Some("1234".to_owned()),
+                remote_url: None,
+                changed: vec!["title", "body"],
+                error: None,
+            },
+            StatusEntry {
+                post: None,
+                platform: "devto",
+                status: SyncStatus::Orphaned,
+                remote_id: Some("5678".to_owned()),
+                remote_url: None,
+                changed: vec![],
+                error: None,
+            },
+        ];
+        let table = render_status(&entries, &ReportFormat::Table)?;
+        let mut lines = table.lines().skip(1);
+        assert_eq!(
+            lines.next().unwrap(),
+            "post.md  devto     out-of-date  1234  changed: title, body"
+        );
+        assert_eq!(
+            lines.next().unwrap(),
+            "-        devto     orphaned     5678"
+        );
+
+        let json: serde_json::Value =
+            serde_json::from_str(&render_status(&entries, &ReportFormat::Json)?)?;
+        assert_eq!(json[0]["status"], "out-of-date");
+        assert_eq!(json[0]["changed"][1], "body");
+        assert!(json[1]["post"].is_null());
+        Ok(())
+    }
+
+    #[test]
+    fn articles() -> Result<()> {
+        let articles = vec![ListedArticle {
+            platform: "devto",
+            id: "1234".to_owned(),
+            title: Some("title".to_owned()),
+            published: Some(false),
+            ..Default::default()
+        }];
+        let table = render_articles(&articles, &ReportFormat::Table)?;
+        assert_eq!(
+            table.lines().nth(1).unwrap(),
+            "devto     1234  no         title"
+        );
+
+        let json: serde_json::Value =
+            serde_json::from_str(&render_articles(&articles, &ReportFormat::Json)?)?;
+        assert_eq!(json[0]["id"], "1234");
+        assert_eq!(json[0]["published"], false);
+        assert!(json[0]["canonical_url"].is_null());
+        Ok(())
+    }
+}
@@ -8,8 +8,8 @@ const MEDIUM_API_TOKEN: &str = "MEDIUM_API_TOKEN";
 const MEDIUM_PUBLICATION_ID: &str = "MEDIUM_PUBLICATION_ID";
 const TUMBLR_CONSUMER_KEY: &str = "TUMBLR_CONSUMER_KEY";
 const TUMBLR_CONSUMER_SECRET: &str = "TUMBLR_CONSUMER_SECRET";
-const TUMBLR_OAUTH_TOKEN: &str = "TUMBLR_OAUTH_TOKEN";
-const TUMBLR_OAUTH_TOKEN_SECRET: &str = "TUMBLR_OAUTH_TOKEN_SECRET";
+pub const TUMBLR_OAUTH_TOKEN: &str = "TUMBLR_OAUTH_TOKEN";
+pub const TUMBLR_OAUTH_TOKEN_SECRET: &str = "TUMBLR_OAUTH_TOKEN_SECRET";
 const TUMBLR_BLOG_ID: &str = "TUMBLR_BLOG_ID";
 
 #[derive(clap::ArgEnum, Clone, Debug, PartialEq)]
@@ -17,6 +17,8 @@ pub enum Operation {
     Auto,
     Create,
     Update,
+    /// Delete (or unpublish, if the platform can't delete) existing articles
+    Delete,
 }
 
 impl Default for Operation {
@@ -47,49 +49,124 @@ pub enum Platforms {
     All,
 }
 
+#[derive(clap::ArgEnum, Clone, Debug, PartialEq)]
+pub enum ReportFormat {
+    Table,
+    Json,
+}
+
+impl Default for ReportFormat {
+    fn default() -> Self {
+        ReportFormat::Table
+    }
+}
+
 #[derive(clap::ArgEnum, Clone, Debug, PartialEq)]
 pub enum UpdateField {
     Body,
     Slug,
     Tags,
+    Title,
+    Description,
+    CanonicalUrl,
+    Series,
+    CoverImage,
+    Published,
 }
 
 #[derive(Clap, Clone, Debug, Default)]
 pub struct Settings {
     /// Dry run (e.g. no REST POST/PUT, GraphQL mutation, etc.)
-    #[clap(long)]
+    #[clap(long, global = true)]
     pub dry: bool,
+    /// Write articles even if unchanged since they were last published
+    #[clap(long, global = true)]
+    pub force: bool,
     /// Posts created as drafts, if possible
-    #[clap(long)]
+    #[clap(long, global = true)]
     pub draft: bool,
-    /// Operation to perform (i.e. update, or submit new)
-    #[clap(long, arg_enum, default_value = "auto")]
+    /// Operation to perform (i.e. update, submit new, or delete)
+    #[clap(long, global = true, arg_enum, default_value = "auto")]
     pub operation: Operation,
     /// How articles are compared to determine if they already exist for update
-    #[clap(long, arg_enum, default_value = "canonical-url")]
+    #[clap(long, global = true, arg_enum, default_value = "canonical-url")]
     pub compare: Compare,
+    /// Maximum pages of existing articles to search on each platform [default: all]
+    #[clap(long, global = true)]
+    pub max_pages: Option<u32>,
     /// Git remote to use
-    #[clap(long, default_value = "origin")]
+    #[clap(long, global = true, default_value = "origin")]
     pub remote: String,
-    /// Publish date if not today
-    #[clap(long)]
-    pub date: Option<String>,
+    /// Publish date if not today (e.g. `2021-05-03 10:00:00 +0900`)
+    #[clap(long, global = true, parse(try_from_str = dates::parse))]
+    pub date: Option<chrono::DateTime<chrono::FixedOffset>>,
     /// YAML file containing configuration
-    #[clap(long, default_value = "$HOME/.bullhorn.yaml")]
+    #[clap(long, global = true, default_value = "$HOME/.bullhorn.yaml")]
     pub config: String,
     /// Override front-matter `slug` value
-    #[clap(long)]
+    #[clap(long, global = true)]
     pub slug: Option<String>,
 
-    /// Article fields to write when updating an article
-    #[clap(long, arg_enum, multiple = true)]
+    /// Write canonical URL, date, and where posts were published into their front matter
+    #[clap(long, global = true)]
+    pub write_back: bool,
+    /// JSON file recording where posts were published [default: `.bullhorn/state.json` in
+    /// the posts' git repository]
+    #[clap(long, global = true)]
+    pub state: Option<String>,
+    /// Format of the report of what was published
+    #[clap(long, global = true, arg_enum, default_value = "table")]
+    pub report: ReportFormat,
+    /// Write the report to a file instead of stdout
+    #[clap(long, global = true)]
+    pub report_file: Option<String>,
+
+    /// Only use posts added or modified in git since this revision (e.g. `HEAD~1`)
+    #[clap(long, global = true)]
+    pub since: Option<String>,
+    /// Only use posts matching this glob pattern (e.g. `_posts/`), may be repeated
+    #[clap(long, global = true, multiple_occurrences = true, number_of_values = 1)]
+    pub include: Vec<String>,
+    /// Skip posts matching this glob pattern (e.g. `_drafts/`), may be repeated
+    #[clap(long, global = true, multiple_occurrences = true, number_of_values = 1)]
+    pub exclude: Vec<String>,
+
+    /// Article fields to write when updating an article [default: all]
+    #[clap(long, global = true, arg_enum, multiple = true)]
     pub update_fields: Vec<UpdateField>,
+}
 
-    /// One or more markdown files
-    #[clap()]
+#[derive(Clap, Clone, Debug)]
+pub enum Command {
+    /// Publish posts to enabled platforms
+    Publish(PostsArgs),
+    /// Log in to a platform and save the credentials to the config file
+    Auth(PlatformArgs),
+    /// List articles on a platform, or `all` enabled platforms
+    List(PlatformArgs),
+    /// Show whether posts are missing, in sync, or out of date on each platform
+    Status(PostsArgs),
+    /// Delete posts from enabled platforms
+    Delete(PostsArgs),
+    /// Check that posts can be parsed
+    Validate(PostsArgs),
+    /// Show the configuration in use
+    Config,
+}
+
+#[derive(Clap, Clone, Debug)]
+pub struct PostsArgs {
+    /// One or more markdown files, directories, or glob patterns
+    #[clap(required = true)]
     pub posts: Vec<String>,
 }
 
+#[derive(Clap, Clone, Debug)]
+pub struct PlatformArgs {
+    #[clap(arg_enum)]
+    pub platform: Platforms,
+}
+
 #[derive(Clap, Debug, Default)]
 #[clap(version = crate_version!())]
 pub struct Opts {
@@ -121,11 +198,96 @@ pub struct Opts {
     pub tumblr_blog_id: Option<String>,
 
     /// Platform(s) to enable.
-    #[clap(long, arg_enum, multiple = true, default_value = "all")]
+    #[clap(long, global = true, arg_enum, multiple = true, default_value = "all")]
     pub platforms: Vec<Platforms>,
 
     #[clap(flatten)]
     pub settings: Settings,
+
+    /// Markdown files to publish without a subcommand, same as `publish`
+    #[clap()]
+    pub posts: Vec<String>,
+
+    #[clap(subcommand)]
+    pub command: Option<Command>,
+}
+
+impl Settings {
+    /// Whether `field` is written when updating an article (all fields if none specified)
+    pub fn is_updated(&self, field: UpdateField) -> bool {
+        self.update_fields.is_empty() || self.update_fields.contains(&field)
+    }
+}
+
+impl Opts {
+    pub fn is_enabled(&self, platform: Platforms) -> bool {
+        self.platforms
+            .iter()
+            .any(|p| *p == platform || *p == Platforms::All)
+    }
+
+    /// Each configuration value, with credentials hidden
+    pub fn to_config(&self) -> Vec<(&'static str, Option<String>)> {
+        let hidden = |value: &Option<String>| value.as_ref().map(|_| "<hidden>".to_owned());
+        vec![
+            (DEVTO_API_TOKEN, hidden(&self.devto_api_token)),
+            (HASHNODE_API_TOKEN, hidden(&self.hashnode_api_token)),
+            (HASHNODE_USERNAME, self.hashnode_username.clone()),
+            (MEDIUM_API_TOKEN, hidden(&self.medium_api_token)),
+            (MEDIUM_PUBLICATION_ID, self.medium_publication_id.clone()),
+            (TUMBLR_CONSUMER_KEY, hidden(&self.tumblr_consumer_key)),
+            (TUMBLR_CONSUMER_SECRET, hidden(&self.tumblr_consumer_secret)),
+            (TUMBLR_OAUTH_TOKEN, hidden(&self.tumblr_token)),
+            (TUMBLR_OAUTH_TOKEN_SECRET, hidden(&self.tumblr_token_secret)),
+            (TUMBLR_BLOG_ID, self.tumblr_blog_id.clone()),
+        ]
+    }
+}
+
+/// Path of the config file, with environment variables expanded
+pub fn config_path(settings: &Settings) -> Result<std::path::PathBuf> {
+    let path = shellexpand::env(&settings.config).map_err(|_| Error::BadString {
+        expected: "config path".to_owned(),
+        found: settings.config.clone(),
+    })?;
+    Ok(std::path::PathBuf::from(path.to_string()))
+}
+
+/// Set `values` in the config file, keeping any other values.  The file is only readable by the
+/// user because it contains credentials.
+pub fn update_config(path: &std::path::Path, values: &[(&str, &str)]) -> Result<()> {
+    let mut text = if path.exists() {
+        std::fs::read_to_string(path)?
+    } else {
+        String::new()
+    };
+    for (key, value) in values {
+        text = set_config_value(&text, key, value)?;
+    }
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        options.mode(0o600);
+        // Mode is only used when creating the file
+        if path.exists() {
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+        }
+    }
+    use std::io::Write;
+    options
+        .open(path)
+        .with_context(|| format!("Unable to write config: {}", path.display()))?
+        .write_all(text.as_bytes())?;
+    Ok(())
+}
+
+/// Replace the lines setting `key` in config `text`, or append them, so comments and the order
+/// of other values are kept
+fn set_config_value(text: &str, key: &str, value: &str) -> Result<String> {
+    post::set_yaml_field(text, key, Some(&value.into()))
 }
 
 pub fn process_config(opts: &mut Opts, config: &str) -> Result<()> {
@@ -206,4 +368,91 @@ mod tests {
         assert_eq!(opts.hashnode_api_token, Some(HASHNODE_API_TOKEN.to_owned()));
         assert_eq!(opts.hashnode_username, Some(HASHNODE_USERNAME.to_owned()));
     }
+
+    #[test]
+    fn subcommands() -> Result<()> {
+        let opts = Opts::try_parse_from(&["cargo_bullhorn", "publish", "--dry", "post.md"])?;
+        assert!(opts.settings.dry);
+        match opts.command {
+            Some(Command::Publish(args)) => assert_eq!(args.posts, vec!["post.md".to_owned()]),
+            command => panic!("Unexpected command: {:?}", command),
+        }
+
+        let opts = Opts::try_parse_from(&["cargo_bullhorn", "--dry", "list", "devto"])?;
+        assert!(opts.settings.dry);
+        match opts.command {
+            Some(Command::List(args)) => assert_eq!(args.platform, Platforms::Devto),
+            command => panic!("Unexpected command: {:?}", command),
+        }
+
+        assert!(Opts::try_parse_from(&["cargo_bullhorn", "publish"]).is_err());
+
+        // Publishes without a subcommand
+        let opts = Opts::try_parse_from(&["cargo_bullhorn", "--dry", "post.md"])?;
+        assert!(opts.settings.dry);
+        assert!(opts.command.is_none());
+        assert_eq!(opts.posts, vec!["post.md".to_owned()]);
+
+        // Patterns take one value each, so the subcommand and posts follow
+        let args = &[
+            "cargo_bullhorn",
+            "--exclude",
+            "x",
+            "--exclude",
+            "y",
+            "publish",
+            ".",
+        ];
+        let opts = Opts::try_parse_from(args)?;
+        assert_eq!(opts.settings.exclude, vec!["x".to_owned(), "y".to_owned()]);
+        match opts.command {
+            Some(Command::Publish(args)) => assert_eq!(args.posts, vec![".".to_owned()]),
+            command => panic!("Unexpected command: {:?}", command),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn update() -> Result<()> {
+        let path = std::env::temp_dir().join(format!("bullhorn-{}.yaml", std::process::id()));
+        let config = format!(
+            "# Credentials\n{}: token\n{}: old\n",
+            DEVTO_API_TOKEN, TUMBLR_OAUTH_TOKEN
+        );
+        std::fs::write(&path, config)?;
+        update_config(&path, &[(TUMBLR_OAUTH_TOKEN, "tumblr")])?;
+        update_config(&path, &[(TUMBLR_OAUTH_TOKEN_SECRET, "a: b")])?;
+        // Comments and order are kept
+        assert_eq!(
+            std::fs::read_to_string(&path)?,
+            format!(
+                "# Credentials\n{}: token\n{}: tumblr\n{}: \"a: b\"\n",
+                DEVTO_API_TOKEN, TUMBLR_OAUTH_TOKEN, TUMBLR_OAUTH_TOKEN_SECRET
+            )
+        );
+
+        let mut opts: Opts = Default::default();
+        process_config(&mut opts, &std::fs::read_to_string(&path)?)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path)?.permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        std::fs::remove_file(&path)?;
+        assert_eq!(opts.devto_api_token, Some("token".to_owned()));
+        assert_eq!(opts.tumblr_token, Some("tumblr".to_owned()));
+        Ok(())
+    }
+
+    #[test]
+    fn set_multiline() -> Result<()> {
+        let text = "a: |\n  line 0\n\n  line 1\nb:\n- 0\n- 1\n\n# c\nc: 2\n";
+        let text = set_config_value(text, "a", "0")?;
+        assert_eq!(text, "a: \"0\"\nb:\n- 0\n- 1\n\n# c\nc: 2\n");
+        let text = set_config_value(&text, "b", "1")?;
+        assert_eq!(text, "a: \"0\"\nb: \"1\"\n\n# c\nc: 2\n");
+        serde_yaml::from_str::<serde_yaml::Mapping>(&text)?;
+        Ok(())
+    }
 }
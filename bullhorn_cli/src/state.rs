@@ -0,0 +1,233 @@
+use crate::{platforms::*, post::Post, *};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Default location of state file relative to root of repository containing posts
+pub const DEFAULT_PATH: &str = ".bullhorn/state.json";
+
+/// Article a post was published as on a platform
+#[derive(Clone, Debug, Default, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct Record {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// `Post::content_hash()` of what was last written to the platform
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+}
+
+impl From<&Record> for RemoteArticle {
+    fn from(item: &Record) -> Self {
+        Self {
+            id: item.id.clone(),
+            url: item.url.clone(),
+        }
+    }
+}
+
+impl From<&post::Crosspost> for Record {
+    fn from(item: &post::Crosspost) -> Self {
+        Self {
+            id: item.id.clone(),
+            url: item.url.clone(),
+            hash: None,
+        }
+    }
+}
+
+/// Ledger of post path -> platform -> published article.
+/// Used to match posts with existing articles without searching each platform.
+#[derive(Debug, Default, serde::Deserialize, serde::Serialize)]
+pub struct State {
+    #[serde(skip)]
+    path: PathBuf,
+    /// Post paths are stored relative to this
+    #[serde(skip)]
+    root: PathBuf,
+    posts: BTreeMap<String, BTreeMap<String, Record>>,
+}
+
+impl State {
+    /// Load state from `path`, or empty state if it doesn't exist yet
+    pub fn open(path: PathBuf, root: PathBuf) -> Result<Self> {
+        let state = if path.is_file() {
+            let text = std::fs::read_to_string(&path)?;
+            serde_json::from_str(&text)
+                .with_context(|| format!("Unable to parse state: {:?}", path))?
+        } else {
+            debug!("No state, starting new: {:?}", path);
+            State::default()
+        };
+        Ok(Self {
+            path,
+            root,
+            ..state
+        })
+    }
+
+    pub fn save(&self) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let text = serde_json::to_string_pretty(self)?;
+        std::fs::write(&self.path, text + "\n")?;
+        debug!("Saved state: {:?}", self.path);
+        Ok(())
+    }
+
+    pub fn get(&self, post: &Post, platform: &str) -> Option<&Record> {
+        self.posts
+            .get(&self.key(&post.path))
+            .and_then(|platforms| platforms.get(platform))
+    }
+
+    pub fn set(&mut self, post: &Post, platform: &str, record: Record) {
+        let key = self.key(&post.path);
+        self.posts
+            .entry(key)
+            .or_default()
+            .insert(platform.to_owned(), record);
+    }
+
+    pub fn remove(&mut self, post: &Post, platform: &str) {
+        let key = self.key(&post.path);
+        if let Some(platforms) = self.posts.get_mut(&key) {
+            platforms.remove(platform);
+            if platforms.is_empty() {
+                self.posts.remove(&key);
+            }
+        }
+    }
+
+    /// Record articles created/updated/matched/deleted when publishing `post`
+    pub fn update(&mut self, post: &Post, outcomes: &[PublishOutcome]) {
+        let hash = post.content_hash().ok();
+        for outcome in outcomes {
+            if outcome.status == Status::Deleted {
+                self.remove(post, outcome.platform);
+                continue;
+            }
+            let id = match &outcome.remote_id {
+                Some(id) if outcome.status != Status::Failed => id.clone(),
+                _ => continue,
+            };
+            let hash = match outcome.status {
+                Status::Created | Status::Updated => hash.clone(),
+                // Keep hash of what was last written
+                _ => self
+                    .get(post, outcome.platform)
+                    .and_then(|record| record.hash.clone()),
+            };
+            let record = Record {
+                id,
+                url: outcome.remote_url.clone(),
+                hash,
+            };
+            self.set(post, outcome.platform, record);
+        }
+    }
+
+    fn key(&self, path: &Path) -> String {
+        let path = path.canonicalize().unwrap_or_else(|_| path.to_owned());
+        let root = self
+            .root
+            .canonicalize()
+            .unwrap_or_else(|_| self.root.clone());
+        let path = path.strip_prefix(&root).unwrap_or(&path);
+        // Same key regardless of platform path separator
+        path.components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+}
+
+/// States of every repository containing posts
+#[derive(Debug, Default)]
+pub struct Ledger {
+    states: BTreeMap<PathBuf, State>,
+}
+
+impl Ledger {
+    /// Get state stored at `path`, loading it if necessary
+    pub fn get_mut(&mut self, path: PathBuf, root: PathBuf) -> Result<&mut State> {
+        if !self.states.contains_key(&path) {
+            let state = State::open(path.clone(), root)?;
+            self.states.insert(path.clone(), state);
+        }
+        Ok(self.states.get_mut(&path).unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outcome(status: Status, remote_id: Option<&str>) -> PublishOutcome {
+        PublishOutcome {
+            platform: "devto",
+            status,
+            remote_id: remote_id.map(String::from),
+            remote_url: None,
+            error: None,
+            preview: None,
+        }
+    }
+
+    #[test]
+    fn key() {
+        let state = State {
+            root: PathBuf::from("/blog"),
+            ..Default::default()
+        };
+        assert_eq!(
+            state.key(Path::new("/blog/_posts/2021-7-1-test.md")),
+            "_posts/2021-7-1-test.md"
+        );
+    }
+
+    #[test]
+    fn update() {
+        let post = Post {
+            path: PathBuf::from("2021-7-1-test.md"),
+            ..Default::default()
+        };
+        let mut state = State::default();
+        state.update(&post, &[outcome(Status::Failed, None)]);
+        assert_eq!(state.get(&post, "devto"), None);
+
+        state.update(&post, &[outcome(Status::Created, Some("1234"))]);
+        let record = state.get(&post, "devto").unwrap();
+        assert_eq!(record.id, "1234");
+        assert_eq!(record.hash, post.content_hash().ok());
+
+        state.update(&post, &[outcome(Status::Deleted, Some("1234"))]);
+        assert_eq!(state.get(&post, "devto"), None);
+        assert!(state.posts.is_empty());
+    }
+
+    #[test]
+    fn round_trip() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("bullhorn_state_{}", std::process::id()));
+        let path = dir.join(DEFAULT_PATH);
+        let post = Post {
+            path: dir.join("2021-7-1-test.md"),
+            ..Default::default()
+        };
+        std::fs::create_dir_all(&dir)?;
+        std::fs::write(&post.path, "")?;
+        let record = Record {
+            id: "1234".to_owned(),
+            url: Some("https://dev.to/user/title".to_owned()),
+            hash: None,
+        };
+        let mut state = State::open(path.clone(), dir.clone())?;
+        state.set(&post, "devto", record.clone());
+        state.save()?;
+
+        let state = State::open(path, dir.clone())?;
+        assert_eq!(state.get(&post, "devto"), Some(&record));
+        std::fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+}